@@ -0,0 +1,267 @@
+//! Reviews of played moves, comparing each move with the best line found by the engine.
+//!
+//! A move is classified by the centipawns it loses against the best move, from the view of the
+//! player making it. Scores are capped at a thousand centipawns and a mate counts as the cap, so
+//! a player who is already far ahead loses little by choosing a slower win. Whole games are
+//! analysed into an [`AnalysisReport`] with the accuracy of each player, which can be written
+//! back to PGN.
+//!
+//! ```rust
+//! use viktoe_chess::analysis::{Analyzer, Classification};
+//! use viktoe_chess::prelude::*;
+//! use viktoe_chess::search::{Engine, Limits};
+//!
+//! let mut analyzer = Analyzer::new(Engine::default(), Limits {
+//!     depth: Some(3),
+//!     ..Limits::default()
+//! });
+//!
+//! // The queen is taken by the pawn on d5 after moving to c4
+//! let game = ChessGame::from_fen(String::from("4k3/8/8/3p4/8/3Q4/8/4K3 w - - 0 1")).unwrap();
+//! let review = analyzer.review_move(&game, &"d3c4".parse().unwrap()).unwrap();
+//!
+//! assert_eq!(review.classification, Classification::Blunder);
+//! assert_eq!(review.hanging, [BoardPosition::from((C, Four))]);
+//! ```
+
+mod report;
+
+use crate::chess_move::ChessMove;
+use crate::position::{self, BoardPosition};
+use crate::search::{Engine, Limits, Score};
+use crate::{ChessError, ChessGame};
+
+pub use report::{AnalysisReport, AnalyzedMove};
+
+// The centipawns a mate counts as, larger scores are capped at it
+const MATE_VALUE: i32 = 1000;
+
+/// The centipawns a move has to lose to be classified as an inaccuracy, a mistake or a blunder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thresholds {
+    pub inaccuracy: i32,
+    pub mistake: i32,
+    pub blunder: i32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            inaccuracy: 50,
+            mistake: 100,
+            blunder: 300,
+        }
+    }
+}
+
+/// How good a played move was compared with the best move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Classification {
+    /// The move the engine would have played
+    Best,
+    /// A move losing less than an inaccuracy
+    Good,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+/// The review of a played move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveReview {
+    pub chess_move: ChessMove,
+    /// The best move of the engine, None if the player had no moves
+    pub best_move: Option<ChessMove>,
+    /// The score of the best move for the player making the move
+    pub best_score: Score,
+    /// The score after the played move for the player making the move
+    pub score: Score,
+    /// The centipawns lost against the best move, never negative
+    pub loss: i32,
+    /// The pieces of the player making the move that the opponent can win after it, see
+    /// [`ChessGame::is_hanging`]
+    pub hanging: Vec<BoardPosition>,
+    /// The number of moves to the mate the best move forced, if the played move does not force
+    /// a mate
+    pub missed_mate: Option<i32>,
+    pub classification: Classification,
+}
+
+/// Reviews moves by searching the positions before and after them
+#[derive(Debug, Clone)]
+pub struct Analyzer {
+    engine: Engine,
+    limits: Limits,
+    thresholds: Thresholds,
+}
+
+impl Analyzer {
+    /// Creates an analyzer searching every position with the engine within the limits
+    pub fn new(engine: Engine, limits: Limits) -> Self {
+        Self {
+            engine,
+            limits,
+            thresholds: Thresholds::default(),
+        }
+    }
+
+    /// Sets the losses that classify a move as an inaccuracy, a mistake or a blunder
+    pub fn set_thresholds(&mut self, thresholds: Thresholds) {
+        self.thresholds = thresholds;
+    }
+
+    /// Reviews a move played in a game, the game is the position before the move. Fails if the
+    /// move can not be made.
+    pub fn review_move(&mut self, game: &ChessGame, chess_move: &ChessMove) -> Result<MoveReview, ChessError> {
+        let mut after = game.clone();
+        after.make_move(chess_move)?;
+
+        let (best_move, best_score) = self.evaluate(game);
+
+        let score = if best_move.as_ref() == Some(chess_move) {
+            best_score
+        } else {
+            from_opponent(self.evaluate(&after).1)
+        };
+
+        Ok(self.review(chess_move, &after, best_move, best_score, score))
+    }
+
+    // Returns the best move and the score of a position for the player whos turn it is, a
+    // finished game is scored from its result
+    pub(crate) fn evaluate(&mut self, game: &ChessGame) -> (Option<ChessMove>, Score) {
+        match game.get_result() {
+            Some(result) => match result.winner {
+                Some(winner) if winner == *game.get_player_turn() => (None, Score::Mate(1)),
+                Some(_) => (None, Score::Mate(0)),
+                None => (None, Score::Centipawns(0)),
+            },
+            None => {
+                let result = self.engine.search(game, &self.limits);
+
+                (result.best_move, result.score)
+            }
+        }
+    }
+
+    // Reviews a move from the best move and score before it and the score after it
+    pub(crate) fn review(
+        &self,
+        chess_move: &ChessMove,
+        after: &ChessGame,
+        best_move: Option<ChessMove>,
+        best_score: Score,
+        score: Score,
+    ) -> MoveReview {
+        let is_best = best_move.as_ref() == Some(chess_move);
+        let score = if is_best { best_score } else { score };
+        let loss = (centipawns(best_score) - centipawns(score)).max(0);
+
+        let player_color = after.get_player_turn().opponent();
+
+        let hanging = position::iter()
+            .into_iter()
+            .filter(|position| {
+                matches!(after.get_square(position), Some(piece) if piece.same_color(&player_color))
+                    && after.is_hanging(position)
+            })
+            .collect();
+
+        let missed_mate = match (best_score, score) {
+            (Score::Mate(moves), Score::Centipawns(_)) if moves > 0 => Some(moves),
+            (Score::Mate(moves), Score::Mate(played)) if moves > 0 && played <= 0 => Some(moves),
+            _ => None,
+        };
+
+        let classification = if is_best {
+            Classification::Best
+        } else if loss >= self.thresholds.blunder {
+            Classification::Blunder
+        } else if loss >= self.thresholds.mistake {
+            Classification::Mistake
+        } else if loss >= self.thresholds.inaccuracy {
+            Classification::Inaccuracy
+        } else {
+            Classification::Good
+        };
+
+        MoveReview {
+            chess_move: chess_move.clone(),
+            best_move,
+            best_score,
+            score,
+            loss,
+            hanging,
+            missed_mate,
+            classification,
+        }
+    }
+}
+
+// Turns the score of the player to move after a move into the score of the player who made it
+pub(crate) fn from_opponent(score: Score) -> Score {
+    match score {
+        Score::Centipawns(centipawns) => Score::Centipawns(-centipawns),
+        // The mate of the player who made the move includes the move
+        Score::Mate(moves) if moves <= 0 => Score::Mate(1 - moves),
+        Score::Mate(moves) => Score::Mate(-moves),
+    }
+}
+
+// Returns the score in centipawns capped at a mate
+pub(crate) fn centipawns(score: Score) -> i32 {
+    match score {
+        Score::Centipawns(centipawns) => centipawns.clamp(-MATE_VALUE, MATE_VALUE),
+        Score::Mate(moves) if moves > 0 => MATE_VALUE,
+        Score::Mate(_) => -MATE_VALUE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn review(fen: &str, chess_move: &str) -> MoveReview {
+        let mut analyzer = Analyzer::new(Engine::default(), Limits {
+            depth: Some(3),
+            ..Limits::default()
+        });
+        let game = ChessGame::from_fen(String::from(fen)).unwrap();
+
+        analyzer.review_move(&game, &chess_move.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn classifies_moves() {
+        // Taking the undefended pawn is the best move
+        let best = review("4k3/8/8/3p4/8/3Q4/8/4K3 w - - 0 1", "d3d5");
+
+        assert_eq!(best.classification, Classification::Best);
+        assert_eq!(best.loss, 0);
+        assert!(best.hanging.is_empty());
+
+        // The rook leaves the back rank instead of mating on a8
+        let missed = review("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", "a1b1");
+
+        assert_eq!(missed.best_move, Some("a1a8".parse().unwrap()));
+        assert_eq!(missed.best_score, Score::Mate(1));
+        assert_eq!(missed.missed_mate, Some(1));
+        assert_eq!(missed.classification, Classification::Blunder);
+
+        // Mating is scored from the result of the game
+        let mate = review("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", "a1a8");
+
+        assert_eq!(mate.score, Score::Mate(1));
+        assert_eq!(mate.missed_mate, None);
+    }
+
+    #[test]
+    fn scores_from_the_opponent() {
+        assert_eq!(from_opponent(Score::Centipawns(30)), Score::Centipawns(-30));
+        assert_eq!(from_opponent(Score::Mate(0)), Score::Mate(1));
+        assert_eq!(from_opponent(Score::Mate(-2)), Score::Mate(3));
+        assert_eq!(from_opponent(Score::Mate(2)), Score::Mate(-2));
+        assert_eq!(centipawns(Score::Centipawns(-5000)), -1000);
+    }
+}
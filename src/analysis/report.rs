@@ -0,0 +1,348 @@
+use std::fmt::Write;
+
+use crate::analysis::{centipawns, from_opponent, Analyzer, Classification, MoveReview};
+use crate::board::Turn;
+use crate::pgn::PgnGame;
+use crate::search::Score;
+use crate::{ChessError, ChessGame};
+
+// The longest line of the exported movetext
+const LINE_LENGTH: usize = 80;
+
+/// A move of an analysed game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyzedMove {
+    /// The number of the move as written in PGN, counted from the start position
+    pub move_number: u16,
+    pub player_color: Turn,
+    /// The move in standard algebraic notation
+    pub san: String,
+    /// The best move in standard algebraic notation, None if the player had no moves
+    pub best_san: Option<String>,
+    /// The score after the move from the view of white, a mate given by the move counts as a
+    /// mate in one
+    pub evaluation: Score,
+    pub review: MoveReview,
+}
+
+/// The analysis of every move of a game
+///
+/// ```rust
+/// use viktoe_chess::analysis::{Analyzer, Classification};
+/// use viktoe_chess::board::Turn;
+/// use viktoe_chess::pgn;
+/// use viktoe_chess::search::{Engine, Limits};
+///
+/// let mut analyzer = Analyzer::new(Engine::default(), Limits {
+///     depth: Some(2),
+///     ..Limits::default()
+/// });
+///
+/// let games = pgn::parse("1. f3 e5 2. g4 Qh4# 0-1");
+/// let report = analyzer.analyze_pgn(&games[0]).unwrap();
+///
+/// assert_eq!(report.moves[2].review.classification, Classification::Blunder);
+/// assert!(report.get_accuracy(&Turn::Black) > report.get_accuracy(&Turn::White));
+/// assert!(report.to_pgn().contains("g4 $4 { [%eval"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalysisReport {
+    /// The tag pairs of the exported PGN
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<AnalyzedMove>,
+    /// `1-0`, `0-1`, `1/2-1/2` or `*` if the game has not finished
+    pub result: String,
+}
+
+impl AnalysisReport {
+    /// Returns the accuracy of a player in percent, the mean of the accuracies of their moves.
+    /// The accuracy of a move drops with the winning chances it loses, as on Lichess. None if
+    /// the player made no moves.
+    pub fn get_accuracy(&self, player_color: &Turn) -> Option<f64> {
+        let accuracies: Vec<f64> = self
+            .moves
+            .iter()
+            .filter(|analyzed| analyzed.player_color == *player_color)
+            .map(|analyzed| {
+                let loss = winning_chances(analyzed.review.best_score) - winning_chances(analyzed.review.score);
+
+                (103.1668 * (-0.04354 * loss.max(0.0)).exp() - 3.1669).clamp(0.0, 100.0)
+            })
+            .collect();
+
+        (!accuracies.is_empty()).then(|| accuracies.iter().sum::<f64>() / accuracies.len() as f64)
+    }
+
+    /// Returns the number of moves of a player with a classification
+    pub fn get_count(&self, player_color: &Turn, classification: Classification) -> usize {
+        self.moves
+            .iter()
+            .filter(|analyzed| {
+                analyzed.player_color == *player_color && analyzed.review.classification == classification
+            })
+            .count()
+    }
+
+    /// Returns the score after each move in centipawns from the view of white, capped at a
+    /// thousand centipawns for a graph of the game
+    pub fn get_evaluation_graph(&self) -> Vec<i32> {
+        self.moves.iter().map(|analyzed| centipawns(analyzed.evaluation)).collect()
+    }
+
+    /// Writes the game as PGN with the evaluation after each move in a `[%eval]` comment. Moves
+    /// that are inaccuracies, mistakes or blunders get the glyphs `$6`, `$2` and `$4` and a
+    /// comment naming the best move.
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            let _ = writeln!(pgn, "[{name} \"{value}\"]");
+        }
+
+        if !self.tags.is_empty() {
+            pgn.push('\n');
+        }
+
+        let mut tokens = Vec::new();
+
+        for analyzed in &self.moves {
+            // Every move follows a comment, so the moves of black are numbered as well
+            tokens.push(match analyzed.player_color {
+                Turn::White => format!("{}.", analyzed.move_number),
+                Turn::Black => format!("{}...", analyzed.move_number),
+            });
+            tokens.push(analyzed.san.clone());
+
+            let (nag, name) = match analyzed.review.classification {
+                Classification::Inaccuracy => (Some("$6"), "Inaccuracy"),
+                Classification::Mistake => (Some("$2"), "Mistake"),
+                Classification::Blunder => (Some("$4"), "Blunder"),
+                Classification::Best | Classification::Good => (None, ""),
+            };
+
+            let mut comment = format!("{{ [%eval {}]", format_evaluation(analyzed.evaluation));
+
+            if let Some(nag) = nag {
+                tokens.push(nag.to_string());
+
+                if let Some(best_san) = &analyzed.best_san {
+                    let _ = write!(comment, " {name}. {best_san} was best.");
+                }
+            }
+
+            comment.push_str(" }");
+            tokens.extend(comment.split(' ').map(String::from));
+        }
+
+        tokens.push(self.result.clone());
+
+        let mut line = String::new();
+
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > LINE_LENGTH {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+
+            line.push_str(&token);
+        }
+
+        pgn.push_str(&line);
+        pgn.push('\n');
+
+        pgn
+    }
+}
+
+impl Analyzer {
+    /// Analyses every move of a game, from the position the game was created in to the current
+    /// position. Each position is searched once.
+    pub fn analyze_game(&mut self, game: &ChessGame) -> Result<AnalysisReport, ChessError> {
+        let mut positions = game.previous_positions.to_vec();
+        positions.push(game.clone());
+
+        if positions.len() != game.get_moves().len() + 1 {
+            return Err(ChessError::InternalError);
+        }
+
+        let evaluations: Vec<_> = positions.iter().map(|position| self.evaluate(position)).collect();
+        let mut moves = Vec::new();
+
+        for (index, chess_move) in game.get_moves().iter().enumerate() {
+            let (before, after) = (&positions[index], &positions[index + 1]);
+            let (best_move, best_score) = evaluations[index].clone();
+            let score = from_opponent(evaluations[index + 1].1);
+
+            let best_san = match &best_move {
+                Some(best_move) => Some(before.to_san(best_move)?),
+                None => None,
+            };
+
+            let review = self.review(chess_move, after, best_move, best_score, score);
+
+            let evaluation = match (&before.turn, review.score) {
+                (Turn::White, score) => score,
+                (Turn::Black, Score::Centipawns(centipawns)) => Score::Centipawns(-centipawns),
+                (Turn::Black, Score::Mate(moves)) => Score::Mate(-moves),
+            };
+
+            moves.push(AnalyzedMove {
+                move_number: before.full_move,
+                player_color: before.turn.clone(),
+                san: before.to_san(chess_move)?,
+                best_san,
+                evaluation,
+                review,
+            });
+        }
+
+        let start = &positions[0];
+        let mut tags = Vec::new();
+
+        if !start.get_variant().is_standard() {
+            tags.push((String::from("Variant"), start.get_variant().name().to_string()));
+        }
+
+        if start.board != start.get_variant().start_position() || start.turn != Turn::White || start.full_move != 1 {
+            tags.push((String::from("SetUp"), String::from("1")));
+            tags.push((String::from("FEN"), start.to_fen()));
+        }
+
+        let result = match game.get_result() {
+            Some(result) => match result.winner {
+                Some(Turn::White) => "1-0",
+                Some(Turn::Black) => "0-1",
+                None => "1/2-1/2",
+            },
+            None => "*",
+        };
+
+        tags.push((String::from("Result"), result.to_string()));
+
+        Ok(AnalysisReport {
+            tags,
+            moves,
+            result: result.to_string(),
+        })
+    }
+
+    /// Analyses the moves of a game read from PGN, keeping its tags and result. Fails if a move
+    /// of the game is illegal.
+    pub fn analyze_pgn(&mut self, pgn: &PgnGame) -> Result<AnalysisReport, ChessError> {
+        let mut report = self.analyze_game(&pgn.to_game()?)?;
+
+        report.tags = pgn.tags.clone();
+        report.result = pgn.result.clone();
+
+        Ok(report)
+    }
+}
+
+// Returns the chances of winning in percent for a score, as used by Lichess
+fn winning_chances(score: Score) -> f64 {
+    50.0 + 50.0 * (2.0 / (1.0 + (-0.00368208 * f64::from(centipawns(score))).exp()) - 1.0)
+}
+
+// Formats a score as in `[%eval]` comments, `0.35` in pawns or `#-2` for a mate
+fn format_evaluation(score: Score) -> String {
+    match score {
+        Score::Centipawns(centipawns) => format!("{:.2}", f64::from(centipawns) / 100.0),
+        Score::Mate(moves) => format!("#{moves}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pgn;
+    use crate::search::{Engine, Limits};
+    use crate::variant::Variant;
+
+    #[test]
+    fn analyses_games() {
+        let mut analyzer = Analyzer::new(Engine::default(), Limits {
+            depth: Some(3),
+            ..Limits::default()
+        });
+
+        let game = pgn::parse("[White \"A\"]\n\n1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0").remove(0);
+        let report = analyzer.analyze_pgn(&game).unwrap();
+
+        assert_eq!(report.moves.len(), 7);
+        assert_eq!(report.moves[5].san, "Nf6");
+        assert_eq!(report.moves[5].review.classification, Classification::Blunder);
+        assert!(report.moves[5].best_san.as_ref().is_some_and(|san| san != "Nf6"));
+        assert_eq!(report.moves[6].review.classification, Classification::Best);
+        assert_eq!(report.moves[6].evaluation, Score::Mate(1));
+        assert_eq!(report.get_count(&Turn::Black, Classification::Blunder), 1);
+        assert_eq!(report.get_evaluation_graph().len(), 7);
+        assert_eq!(report.get_evaluation_graph()[6], 1000);
+        assert!(report.get_accuracy(&Turn::White).unwrap() > report.get_accuracy(&Turn::Black).unwrap());
+
+        // The exported game is read back with the same moves and tags
+        let exported = report.to_pgn();
+
+        assert!(exported.contains("3... Nf6 $4 { [%eval"));
+        assert!(exported.contains("was best. }"));
+        assert!(exported.lines().all(|line| line.len() <= 80));
+
+        let read = pgn::parse(&exported).remove(0);
+
+        assert_eq!(read.moves, game.moves);
+        assert_eq!(read.tags, game.tags);
+        assert_eq!(read.result, "1-0");
+    }
+
+    #[test]
+    fn analyses_positions() {
+        let mut analyzer = Analyzer::new(Engine::default(), Limits {
+            depth: Some(2),
+            ..Limits::default()
+        });
+
+        let mut game = ChessGame::from_fen(String::from("4k3/8/8/8/8/8/4P3/4K3 b - - 0 40")).unwrap();
+        game.make_move(&"e8d7".parse().unwrap()).unwrap();
+        game.make_move(&"e2e4".parse().unwrap()).unwrap();
+
+        let report = analyzer.analyze_game(&game).unwrap();
+
+        assert_eq!(report.moves[0].move_number, 40);
+        assert_eq!(report.moves[1].move_number, 41);
+        assert!(report.get_accuracy(&Turn::White).is_some());
+        assert_eq!(report.tags[0].0, "SetUp");
+        assert_eq!(report.result, "*");
+        assert!(report.to_pgn().contains("*\n"));
+        assert!(report.to_pgn().contains("40... Kd7"));
+    }
+
+    #[test]
+    fn tags_variants_by_their_rules() {
+        // A variant named like standard chess is still written with its name
+        struct Renamed;
+
+        impl Variant for Renamed {
+            fn name(&self) -> &'static str {
+                "standard"
+            }
+        }
+
+        let mut analyzer = Analyzer::new(Engine::default(), Limits {
+            depth: Some(1),
+            ..Limits::default()
+        });
+
+        let report = analyzer.analyze_game(&ChessGame::new(Renamed)).unwrap();
+
+        assert_eq!(report.tags[0], (String::from("Variant"), String::from("standard")));
+
+        let report = analyzer.analyze_game(&ChessGame::default()).unwrap();
+
+        assert_eq!(report.tags, [(String::from("Result"), String::from("*"))]);
+    }
+}
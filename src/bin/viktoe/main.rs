@@ -0,0 +1,19 @@
+//! A chess engine speaking the UCI or the CECP (xboard) protocol over stdin and stdout, the
+//! protocol is chosen by the first command
+
+mod thread;
+mod uci;
+mod xboard;
+
+use std::io::{self, BufRead};
+use std::iter;
+
+fn main() {
+    let mut lines = io::stdin().lock().lines().map_while(Result::ok);
+
+    match lines.next() {
+        Some(line) if line.trim() == "xboard" => xboard::run(lines),
+        Some(line) => uci::run(iter::once(line).chain(lines)),
+        None => {}
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use viktoe_chess::prelude::*;
+use viktoe_chess::search::{Engine, Limits, SearchResult};
+
+/// A search running on its own thread, the engine is handed back when the search is done
+pub struct SearchThread {
+    handle: JoinHandle<Engine>,
+    stop: Arc<AtomicBool>,
+}
+
+impl SearchThread {
+    /// Starts searching on a new thread, calling `on_info` after each iteration and `on_finish`
+    /// with the result. An infinite search waits until it is stopped before it finishes.
+    pub fn start(
+        mut engine: Engine,
+        game: ChessGame,
+        limits: Limits,
+        infinite: bool,
+        on_info: impl FnMut(&SearchResult) + Send + 'static,
+        on_finish: impl FnOnce(SearchResult) + Send + 'static,
+    ) -> Self {
+        let stop = engine.get_stop_handle();
+
+        // Reset before the thread starts so a stop sent right after can not be lost
+        stop.store(false, Ordering::Relaxed);
+
+        let handle = {
+            let stop = Arc::clone(&stop);
+
+            thread::spawn(move || {
+                let result = engine.search_with_info(&game, &limits, on_info);
+
+                while infinite && !stop.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(5));
+                }
+
+                on_finish(result);
+
+                engine
+            })
+        };
+
+        Self { handle, stop }
+    }
+
+    /// Stops the search and waits for it to finish
+    pub fn stop(self) -> Engine {
+        self.stop.store(true, Ordering::Relaxed);
+        self.wait()
+    }
+
+    /// Waits for the search to finish without stopping it
+    pub fn wait(self) -> Engine {
+        self.handle.join().expect("The search thread should not panic")
+    }
+}
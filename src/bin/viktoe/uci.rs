@@ -0,0 +1,356 @@
+use std::env;
+use std::slice;
+use std::sync::Arc;
+use std::time::Duration;
+
+use viktoe_chess::board::Turn;
+use viktoe_chess::book::Book;
+use viktoe_chess::prelude::*;
+use viktoe_chess::search::{Clock, Engine, Limits, Line, SearchResult, DEFAULT_HASH_SIZE};
+use viktoe_chess::tablebase::Tablebase;
+
+use crate::thread::SearchThread;
+
+const START_POSITION: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+const MAX_HASH_SIZE: usize = 1024;
+
+const MAX_MULTI_PV: usize = 64;
+
+const MAX_THREADS: usize = 256;
+
+/// The parameters of a go command
+#[derive(Debug, Default, PartialEq)]
+struct Go {
+    limits: Limits,
+    white_time: Option<Duration>,
+    black_time: Option<Duration>,
+    white_increment: Option<Duration>,
+    black_increment: Option<Duration>,
+    moves_to_go: Option<u32>,
+    infinite: bool,
+}
+
+struct Uci {
+    engine: Option<Engine>,
+    search: Option<SearchThread>,
+    game: ChessGame,
+    book: Option<Book>,
+    own_book: bool,
+}
+
+/// Reads UCI commands until `quit` or the end of the input
+pub fn run(lines: impl Iterator<Item = String>) {
+    let mut uci = Uci {
+        engine: Some(Engine::default()),
+        search: None,
+        game: ChessGame::default(),
+        book: None,
+        own_book: false,
+    };
+
+    for line in lines {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.as_slice() {
+            ["uci", ..] => {
+                println!("id name viktoe-chess {}", env!("CARGO_PKG_VERSION"));
+                println!("id author viktoe");
+                println!(
+                    "option name Hash type spin default {DEFAULT_HASH_SIZE} min 1 max {MAX_HASH_SIZE}"
+                );
+                println!("option name Threads type spin default 1 min 1 max {MAX_THREADS}");
+                println!("option name MultiPV type spin default 1 min 1 max {MAX_MULTI_PV}");
+                println!("option name OwnBook type check default false");
+                println!("option name BookFile type string default <empty>");
+                println!("option name SyzygyPath type string default <empty>");
+                println!("uciok");
+            }
+            ["isready", ..] => println!("readyok"),
+            ["ucinewgame", ..] => {
+                uci.engine().clear();
+                uci.game = ChessGame::default();
+            }
+            ["setoption", arguments @ ..] => uci.set_option(arguments),
+            ["position", arguments @ ..] => match parse_position(arguments) {
+                Ok(game) => uci.game = game,
+                Err(error) => println!("info string invalid position {error:?}"),
+            },
+            ["go", arguments @ ..] => uci.go(parse_go(arguments)),
+            ["stop", ..] | ["ponderhit", ..] => {
+                if let Some(search) = uci.search.take() {
+                    uci.engine = Some(search.stop());
+                }
+            }
+            ["d", ..] => println!("info string {}", uci.game.to_fen()),
+            ["quit", ..] => break,
+            _ => {}
+        }
+    }
+
+    if let Some(search) = uci.search.take() {
+        search.stop();
+    }
+}
+
+impl Uci {
+    // Returns the engine, stopping the search if one is running
+    fn engine(&mut self) -> &mut Engine {
+        if let Some(search) = self.search.take() {
+            self.engine = Some(search.stop());
+        }
+
+        self.engine
+            .as_mut()
+            .expect("The engine is only missing while searching")
+    }
+
+    fn set_option(&mut self, arguments: &[&str]) {
+        let (name, value) = match arguments {
+            ["name", name, "value", value @ ..] => (name.to_ascii_lowercase(), value.join(" ")),
+            _ => return,
+        };
+
+        match (name.as_str(), value.parse::<usize>()) {
+            ("hash", Ok(megabytes)) => self.engine().set_hash_size(megabytes.clamp(1, MAX_HASH_SIZE)),
+            ("multipv", Ok(lines)) => self.engine().set_multi_pv(lines.clamp(1, MAX_MULTI_PV)),
+            ("threads", Ok(threads)) => self.engine().set_threads(threads.clamp(1, MAX_THREADS)),
+            ("ownbook", _) => self.own_book = value == "true",
+            ("bookfile", _) if value == "<empty>" || value.is_empty() => self.book = None,
+            ("bookfile", _) => match Book::open(&value) {
+                Ok(book) => self.book = Some(book),
+                Err(error) => println!("info string could not read book {value} {error}"),
+            },
+            ("syzygypath", _) if value == "<empty>" || value.is_empty() => self.engine().set_tablebase(None),
+            ("syzygypath", _) => self.set_tablebase(&value),
+            _ => println!("info string unsupported option {name} {value}"),
+        }
+    }
+
+    // Reads the tables of every directory in the path, separated as in the PATH variable
+    fn set_tablebase(&mut self, path: &str) {
+        let mut tablebase = Tablebase::new();
+        let mut count = 0;
+
+        for directory in env::split_paths(path) {
+            match tablebase.add_directory(&directory) {
+                Ok(tables) => count += tables,
+                Err(error) => println!("info string could not read {} {error}", directory.display()),
+            }
+        }
+
+        println!(
+            "info string found {count} tablebase files with up to {} pieces",
+            tablebase.get_max_pieces()
+        );
+
+        self.engine().set_tablebase(Some(Arc::new(tablebase)));
+    }
+
+    fn go(&mut self, mut go: Go) {
+        if let Some(search) = self.search.take() {
+            self.engine = Some(search.stop());
+        }
+
+        // Play from the book without searching, unless the GUI waits for a stop
+        if let (true, Some(book), false) = (self.own_book, &self.book, go.infinite) {
+            if let Some(chess_move) = book.get_random_move(&self.game) {
+                println!("bestmove {chess_move}");
+                return;
+            }
+        }
+
+        let engine = self.engine.take().expect("The engine is only missing while searching");
+
+        go.limits.clock = get_clock(&go, self.game.get_player_turn());
+
+        self.search = Some(SearchThread::start(
+            engine,
+            self.game.clone(),
+            go.limits,
+            go.infinite,
+            |result| println!("{}", format_info(result)),
+            |result| match result.principal_variation.as_slice() {
+                [best_move, ponder, ..] => println!("bestmove {best_move} ponder {ponder}"),
+                [best_move] => println!("bestmove {best_move}"),
+                [] => println!("bestmove 0000"),
+            },
+        ));
+    }
+}
+
+// Parses `startpos` or `fen <fen>` followed by `moves <moves>`
+fn parse_position(arguments: &[&str]) -> Result<ChessGame, ChessError> {
+    let (fen, moves) = match arguments {
+        ["startpos", rest @ ..] => (START_POSITION.to_string(), rest),
+        ["fen", rest @ ..] => {
+            let length = rest.iter().position(|token| *token == "moves").unwrap_or(rest.len());
+            let mut fen = rest[..length].to_vec();
+
+            // The move counters are optional in some GUIs
+            if fen.len() == 4 {
+                fen.extend(["0", "1"]);
+            }
+
+            (fen.join(" "), &rest[length..])
+        }
+        _ => return Err(ChessError::IncorrectFenString),
+    };
+
+    let mut game = ChessGame::from_fen(fen)?;
+
+    if let ["moves", moves @ ..] = moves {
+        for chess_move in moves {
+            game.make_move(&chess_move.parse()?)?;
+        }
+    }
+
+    Ok(game)
+}
+
+fn parse_go(arguments: &[&str]) -> Go {
+    let mut go = Go::default();
+    let mut tokens = arguments.iter();
+
+    while let Some(token) = tokens.next() {
+        let mut number = || tokens.next().and_then(|value| value.parse::<u64>().ok());
+
+        match *token {
+            "wtime" => go.white_time = number().map(Duration::from_millis),
+            "btime" => go.black_time = number().map(Duration::from_millis),
+            "winc" => go.white_increment = number().map(Duration::from_millis),
+            "binc" => go.black_increment = number().map(Duration::from_millis),
+            "movestogo" => go.moves_to_go = number().map(|moves| moves as u32),
+            "depth" => go.limits.depth = number().map(|depth| depth.min(u64::from(u8::MAX)) as u8),
+            "nodes" => go.limits.nodes = number(),
+            "mate" => go.limits.depth = number().map(|moves| (moves * 2).min(u64::from(u8::MAX)) as u8),
+            "movetime" => go.limits.movetime = number().map(Duration::from_millis),
+            "infinite" | "ponder" => go.infinite = true,
+            _ => {}
+        }
+    }
+
+    go
+}
+
+// Returns the clock sent by the GUI, if it sent the time of the player to move
+fn get_clock(go: &Go, turn: &Turn) -> Option<Clock> {
+    let time = match turn {
+        Turn::White => go.white_time?,
+        Turn::Black => go.black_time?,
+    };
+
+    Some(Clock {
+        white_time: go.white_time.unwrap_or(time),
+        black_time: go.black_time.unwrap_or(time),
+        white_increment: go.white_increment.unwrap_or_default(),
+        black_increment: go.black_increment.unwrap_or_default(),
+        moves_to_go: go.moves_to_go,
+    })
+}
+
+// Formats an info line for each line of the search
+fn format_info(result: &SearchResult) -> String {
+    let milliseconds = result.time.as_millis().max(1);
+
+    // A position without moves has no lines
+    let main_line = Line {
+        score: result.score,
+        principal_variation: result.principal_variation.clone(),
+    };
+    let lines = if result.lines.is_empty() {
+        slice::from_ref(&main_line)
+    } else {
+        result.lines.as_slice()
+    };
+
+    let info: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let principal_variation: Vec<String> =
+                line.principal_variation.iter().map(ToString::to_string).collect();
+
+            format!(
+                "info depth {} multipv {} score {} nodes {} nps {} tbhits {} time {} pv {}",
+                result.depth,
+                index + 1,
+                line.score,
+                result.nodes,
+                u128::from(result.nodes) * 1000 / milliseconds,
+                result.tablebase_hits,
+                result.time.as_millis(),
+                principal_variation.join(" ")
+            )
+        })
+        .collect();
+
+    info.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_positions() {
+        let game = parse_position(&["startpos", "moves", "e2e4", "e7e5", "g1f3"]).unwrap();
+
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+
+        let game = parse_position(&["fen", "4k3/8/8/8/8/8/8/R3K3", "w", "Q", "-", "moves", "e1c1"]).unwrap();
+
+        assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/2KR4 b - - 1 1");
+
+        assert!(parse_position(&["startpos", "moves", "e2e5"]).is_err());
+    }
+
+    #[test]
+    fn parses_go() {
+        let go = parse_go(&["wtime", "60000", "btime", "30000", "winc", "1000", "movestogo", "20", "depth", "6"]);
+
+        assert_eq!(go.white_time, Some(Duration::from_secs(60)));
+        assert_eq!(go.black_time, Some(Duration::from_secs(30)));
+        assert_eq!(go.white_increment, Some(Duration::from_secs(1)));
+        assert_eq!(go.moves_to_go, Some(20));
+        assert_eq!(go.limits.depth, Some(6));
+        assert!(!go.infinite);
+
+        assert!(parse_go(&["infinite"]).infinite);
+        assert_eq!(parse_go(&["movetime", "500"]).limits.movetime, Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn formats_every_line() {
+        let mut engine = Engine::default();
+        engine.set_multi_pv(2);
+
+        let result = engine.search(&ChessGame::default(), &Limits {
+            depth: Some(2),
+            ..Limits::default()
+        });
+        let info = format_info(&result);
+        let lines: Vec<&str> = info.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("info depth 2 multipv 1 score cp "));
+        assert!(lines[1].starts_with("info depth 2 multipv 2 score cp "));
+    }
+
+    #[test]
+    fn reads_the_clock() {
+        let go = parse_go(&["wtime", "60000", "btime", "100", "winc", "1000", "movestogo", "20"]);
+        let clock = get_clock(&go, &Turn::Black).unwrap();
+
+        assert_eq!(clock.get_time(&Turn::White), Duration::from_secs(60));
+        assert_eq!(clock.get_time(&Turn::Black), Duration::from_millis(100));
+        assert_eq!(clock.get_increment(&Turn::White), Duration::from_secs(1));
+        assert_eq!(clock.get_increment(&Turn::Black), Duration::ZERO);
+        assert_eq!(clock.moves_to_go, Some(20));
+        assert_eq!(get_clock(&parse_go(&["btime", "100"]), &Turn::White), None);
+        assert_eq!(get_clock(&Go::default(), &Turn::White), None);
+    }
+}
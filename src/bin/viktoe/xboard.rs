@@ -0,0 +1,477 @@
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use viktoe_chess::board::{Termination, Turn};
+use viktoe_chess::chess_move::ChessMove;
+use viktoe_chess::prelude::*;
+use viktoe_chess::search::{Clock, Engine, Limits, Score, SearchResult};
+use viktoe_chess::tablebase::Tablebase;
+
+use crate::thread::SearchThread;
+
+/// The time control set by `level`
+#[derive(Debug, Clone, PartialEq)]
+struct Level {
+    /// The number of moves per session, or None if the whole game is one session
+    moves_per_session: Option<u32>,
+    base: Duration,
+    increment: Duration,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Self {
+            moves_per_session: Some(40),
+            base: Duration::from_secs(5 * 60),
+            increment: Duration::ZERO,
+        }
+    }
+}
+
+// The move of a finished search, shared with the search thread
+#[derive(Default)]
+struct Pending {
+    // Set when the position changed while searching, the move is not sent
+    discard: bool,
+    // The move that was sent but not yet played on the game
+    best_move: Option<ChessMove>,
+}
+
+struct Xboard {
+    engine: Option<Engine>,
+    search: Option<SearchThread>,
+    pending: Arc<Mutex<Pending>>,
+    game: ChessGame,
+    force: bool,
+    engine_turn: Turn,
+    post: bool,
+    depth: Option<u8>,
+    time_per_move: Option<Duration>,
+    level: Level,
+    time: Option<Duration>,
+}
+
+/// Reads CECP commands, after the initial `xboard`, until `quit` or the end of the input
+pub fn run(lines: impl Iterator<Item = String>) {
+    let mut xboard = Xboard {
+        engine: Some(Engine::default()),
+        search: None,
+        pending: Arc::default(),
+        game: ChessGame::default(),
+        force: false,
+        engine_turn: Turn::Black,
+        post: false,
+        depth: None,
+        time_per_move: None,
+        level: Level::default(),
+        time: None,
+    };
+
+    for line in lines {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        xboard.collect_move();
+
+        match tokens.as_slice() {
+            ["protover", ..] => println!(
+                "feature myname=\"viktoe-chess {}\" setboard=1 usermove=1 ping=1 memory=1 smp=1 \
+                 egt=\"syzygy\" sigint=0 sigterm=0 colors=0 reuse=1 analyze=0 done=1",
+                env!("CARGO_PKG_VERSION")
+            ),
+            ["new", ..] => {
+                xboard.abort_search();
+                xboard.engine().clear();
+                xboard.game = ChessGame::default();
+                xboard.force = false;
+                xboard.engine_turn = Turn::Black;
+                xboard.depth = None;
+                xboard.time = None;
+            }
+            ["force", ..] => {
+                xboard.abort_search();
+                xboard.force = true;
+            }
+            ["go", ..] => {
+                xboard.abort_search();
+                xboard.force = false;
+                xboard.engine_turn = xboard.game.get_player_turn().clone();
+                xboard.think();
+            }
+            ["usermove", chess_move] => xboard.user_move(chess_move),
+            ["?", ..] => xboard.move_now(),
+            ["undo", ..] => {
+                xboard.abort_search();
+                let _ = xboard.game.undo_move();
+            }
+            ["remove", ..] => {
+                xboard.abort_search();
+                let _ = xboard.game.undo_move();
+                let _ = xboard.game.undo_move();
+            }
+            ["setboard", fen @ ..] => {
+                xboard.abort_search();
+
+                match ChessGame::from_fen(fen.join(" ")) {
+                    Ok(game) => xboard.game = game,
+                    Err(error) => println!("tellusererror Illegal position {error:?}"),
+                }
+            }
+            ["level", arguments @ ..] => match parse_level(arguments) {
+                Some(level) => {
+                    xboard.level = level;
+                    xboard.time_per_move = None;
+                }
+                None => println!("Error (invalid level): {line}"),
+            },
+            ["st", seconds] => match seconds.parse::<f64>() {
+                Ok(seconds) if seconds > 0.0 => xboard.time_per_move = Some(Duration::from_secs_f64(seconds)),
+                _ => println!("Error (invalid time): {line}"),
+            },
+            ["sd", depth] => match depth.parse::<u8>() {
+                Ok(depth) => xboard.depth = Some(depth.max(1)),
+                Err(_) => println!("Error (invalid depth): {line}"),
+            },
+            ["time", centiseconds] => {
+                xboard.time = centiseconds.parse().ok().map(Duration::from_millis).map(|time| time * 10);
+            }
+            ["result", ..] => {
+                xboard.abort_search();
+                xboard.force = true;
+            }
+            ["ping", number] => {
+                // A move being searched is sent before the pong
+                xboard.wait_for_move();
+                println!("pong {number}");
+            }
+            ["post", ..] => xboard.post = true,
+            ["nopost", ..] => xboard.post = false,
+            ["memory", megabytes] => {
+                if let Ok(megabytes) = megabytes.parse::<usize>() {
+                    xboard.engine().set_hash_size(megabytes.max(1));
+                }
+            }
+            ["cores", cores] => {
+                if let Ok(cores) = cores.parse::<usize>() {
+                    xboard.engine().set_threads(cores);
+                }
+            }
+            ["egtpath", "syzygy", path @ ..] => {
+                let mut tablebase = Tablebase::new();
+
+                for directory in env::split_paths(&path.join(" ")) {
+                    if let Err(error) = tablebase.add_directory(&directory) {
+                        println!("# could not read {} {error}", directory.display());
+                    }
+                }
+
+                xboard.engine().set_tablebase(Some(Arc::new(tablebase)));
+            }
+            ["quit", ..] => break,
+            // Commands that do not affect the engine
+            ["xboard" | "accepted" | "rejected" | "otim" | "hard" | "easy" | "random" | "computer"
+            | "name" | "rating" | "draw" | "ics", ..]
+            | [] => {}
+            [command, ..] => println!("Error (unknown command): {command}"),
+        }
+    }
+
+    xboard.abort_search();
+}
+
+impl Xboard {
+    // Returns the engine, stopping the search without sending its move if one is running
+    fn engine(&mut self) -> &mut Engine {
+        self.abort_search();
+
+        self.engine
+            .as_mut()
+            .expect("The engine is only missing while searching")
+    }
+
+    fn user_move(&mut self, chess_move: &str) {
+        self.abort_search();
+
+        let result = chess_move
+            .parse::<ChessMove>()
+            .and_then(|parsed| self.game.make_move(&parsed));
+
+        match result {
+            Ok(_) => {
+                if let Some(result) = format_result(&self.game) {
+                    println!("{result}");
+                } else if !self.force && *self.game.get_player_turn() == self.engine_turn {
+                    self.think();
+                }
+            }
+            Err(_) => println!("Illegal move: {chess_move}"),
+        }
+    }
+
+    fn think(&mut self) {
+        if self.game.get_result().is_some() {
+            return;
+        }
+
+        let engine = self.engine.take().expect("The engine is only missing while searching");
+
+        let limits = Limits {
+            depth: self.depth,
+            nodes: None,
+            movetime: self.time_per_move,
+            clock: self.get_clock(),
+        };
+
+        let post = self.post;
+        let pending = Arc::new(Mutex::new(Pending::default()));
+        self.pending = Arc::clone(&pending);
+
+        let mut game = self.game.clone();
+
+        self.search = Some(SearchThread::start(
+            engine,
+            self.game.clone(),
+            limits,
+            false,
+            move |result| {
+                if post {
+                    println!("{}", format_thinking(result));
+                }
+            },
+            move |result| {
+                let mut pending = pending.lock().expect("The lock should not be poisoned");
+
+                if pending.discard {
+                    return;
+                }
+
+                if let Some(best_move) = result.best_move {
+                    println!("move {best_move}");
+
+                    if game.make_move(&best_move).is_ok() {
+                        if let Some(result) = format_result(&game) {
+                            println!("{result}");
+                        }
+                    }
+
+                    pending.best_move = Some(best_move);
+                }
+            },
+        ));
+    }
+
+    // Returns the clock of the level, the time of the opponent is not used. None when searching
+    // for a fixed time per move.
+    fn get_clock(&self) -> Option<Clock> {
+        if self.time_per_move.is_some() {
+            return None;
+        }
+
+        let moves_to_go = self.level.moves_per_session.map(|moves_per_session| {
+            let moves_played = (self.game.get_moves().len() / 2) as u32;
+
+            moves_per_session - moves_played % moves_per_session
+        });
+
+        Some(Clock {
+            moves_to_go,
+            ..Clock::new(self.time.unwrap_or(self.level.base), self.level.increment)
+        })
+    }
+
+    // Plays the move of a search that has finished, so the game matches what was sent
+    fn collect_move(&mut self) {
+        let finished = self
+            .pending
+            .lock()
+            .expect("The lock should not be poisoned")
+            .best_move
+            .is_some();
+
+        if finished {
+            self.wait_for_move();
+        }
+    }
+
+    // Waits for the search to send its move and plays it
+    fn wait_for_move(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.engine = Some(search.wait());
+        }
+
+        self.play_pending_move();
+    }
+
+    // Stops the search and sends the best move found so far
+    fn move_now(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.engine = Some(search.stop());
+        }
+
+        self.play_pending_move();
+    }
+
+    // Stops the search without sending its move, unless it was already sent
+    fn abort_search(&mut self) {
+        self.pending
+            .lock()
+            .expect("The lock should not be poisoned")
+            .discard = true;
+
+        if let Some(search) = self.search.take() {
+            self.engine = Some(search.stop());
+        }
+
+        self.play_pending_move();
+    }
+
+    fn play_pending_move(&mut self) {
+        let best_move = self
+            .pending
+            .lock()
+            .expect("The lock should not be poisoned")
+            .best_move
+            .take();
+
+        if let Some(best_move) = best_move {
+            let _ = self.game.make_move(&best_move);
+        }
+    }
+}
+
+// Parses `MPS BASE INC` where the base is in minutes or `minutes:seconds`
+fn parse_level(arguments: &[&str]) -> Option<Level> {
+    let [moves_per_session, base, increment] = arguments else {
+        return None;
+    };
+
+    let moves_per_session = match moves_per_session.parse::<u32>().ok()? {
+        0 => None,
+        moves => Some(moves),
+    };
+
+    let base = match base.split_once(':') {
+        Some((minutes, seconds)) => {
+            Duration::from_secs(minutes.parse::<u64>().ok()? * 60 + seconds.parse::<u64>().ok()?)
+        }
+        None => Duration::from_secs_f64(base.parse::<f64>().ok().filter(|base| *base >= 0.0)? * 60.0),
+    };
+
+    let increment = Duration::from_secs_f64(increment.parse::<f64>().ok().filter(|increment| *increment >= 0.0)?);
+
+    Some(Level {
+        moves_per_session,
+        base,
+        increment,
+    })
+}
+
+// Formats a completed iteration as `ply score time nodes pv`, with the time in centiseconds and
+// mates as 100000 plus the number of moves
+fn format_thinking(result: &SearchResult) -> String {
+    let score = match result.score {
+        Score::Centipawns(centipawns) => centipawns,
+        Score::Mate(moves) if moves > 0 => 100_000 + moves,
+        Score::Mate(moves) => -100_000 + moves,
+    };
+
+    let principal_variation: Vec<String> =
+        result.principal_variation.iter().map(ToString::to_string).collect();
+
+    format!(
+        "{} {} {} {} {}",
+        result.depth,
+        score,
+        result.time.as_millis() / 10,
+        result.nodes,
+        principal_variation.join(" ")
+    )
+}
+
+// Formats the result of a finished game as `1-0 {reason}`
+fn format_result(game: &ChessGame) -> Option<String> {
+    let result = game.get_result()?;
+
+    let (score, winner) = match result.winner {
+        Some(Turn::White) => ("1-0", "White"),
+        Some(Turn::Black) => ("0-1", "Black"),
+        None => ("1/2-1/2", "Neither"),
+    };
+
+    let reason = match result.termination {
+        Termination::CheckMate => format!("{winner} mates"),
+        Termination::FiftyMoveRule => String::from("50 move rule"),
+        Termination::ThreeCheck => format!("{winner} gives three checks"),
+        Termination::KingOfTheHill => format!("{winner} king reaches the centre"),
+        Termination::Explosion => format!("{winner} explodes the king"),
+        Termination::AllPiecesLost => String::from("All pieces lost"),
+        Termination::Stalemate => String::from("Stalemate"),
+        Termination::RaceFinished if result.winner.is_none() => String::from("Both kings reach the last rank"),
+        Termination::RaceFinished => format!("{winner} king reaches the last rank"),
+    };
+
+    Some(format!("{score} {{{reason}}}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_levels() {
+        assert_eq!(
+            parse_level(&["40", "5", "0"]),
+            Some(Level {
+                moves_per_session: Some(40),
+                base: Duration::from_secs(300),
+                increment: Duration::ZERO,
+            })
+        );
+
+        assert_eq!(
+            parse_level(&["0", "2:30", "1.5"]),
+            Some(Level {
+                moves_per_session: None,
+                base: Duration::from_secs(150),
+                increment: Duration::from_millis(1500),
+            })
+        );
+
+        assert_eq!(parse_level(&["40", "five", "0"]), None);
+        assert_eq!(parse_level(&["40", "5"]), None);
+    }
+
+    #[test]
+    fn formats_results() {
+        let mut game = ChessGame::default();
+
+        assert_eq!(format_result(&game), None);
+
+        for chess_move in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+            game.make_move(&chess_move.parse().unwrap()).unwrap();
+        }
+
+        assert_eq!(format_result(&game), Some("0-1 {Black mates}".to_string()));
+
+        let mut game = ChessGame::from_fen(String::from("4k3/8/8/8/8/8/8/4K3 w - - 99 80")).unwrap();
+        game.make_move(&"e1e2".parse().unwrap()).unwrap();
+
+        assert_eq!(format_result(&game), Some("1/2-1/2 {50 move rule}".to_string()));
+    }
+
+    #[test]
+    fn formats_thinking_output() {
+        let result = SearchResult {
+            best_move: Some("e2e4".parse().unwrap()),
+            score: Score::Mate(3),
+            depth: 5,
+            nodes: 1234,
+            principal_variation: vec!["e2e4".parse().unwrap(), "e7e5".parse().unwrap()],
+            time: Duration::from_millis(1500),
+            tablebase_hits: 0,
+            lines: Vec::new(),
+        };
+
+        assert_eq!(format_thinking(&result), "5 100003 150 1234 e2e4 e7e5");
+    }
+}
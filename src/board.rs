@@ -1,31 +1,76 @@
 mod serilize;
 mod trait_implementation;
+mod zobrist;
 pub(crate) mod check;
+mod attacks;
+mod exchange;
+mod pins;
 
 use crate::piece::{
     Color, Piece,
 };
-use crate::position::{BoardPosition, File::*, Rank::*};
+use crate::position::BoardPosition;
 
-#[derive(Clone)]
+pub use attacks::AttackMap;
+pub use pins::Pin;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Turn {
     White,
     Black,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub enum GameState {
     Ongoing,
     Check,
     CheckMate,
-    Draw,
+    /// The game was drawn, for the reason in the termination
+    Draw(Termination),
     Promotion(BoardPosition, Color<Piece>, MoveType),
+    /// The game was won by a player due to a rule of the variant
+    Win(Turn, Termination),
+}
+
+/// The reason a game ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Termination {
+    CheckMate,
+    FiftyMoveRule,
+    ThreeCheck,
+    KingOfTheHill,
+    Explosion,
+    /// The player lost all their pieces, a win in antichess
+    AllPiecesLost,
+    /// The player had no moves without being in check, a win in antichess
+    Stalemate,
+    /// A king reached the last rank in racing kings
+    RaceFinished,
+}
+
+/// The outcome of a finished game, `winner` is None if the game was drawn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameResult {
+    pub winner: Option<Turn>,
+    pub termination: Termination,
 }
 
 #[derive(Debug, Clone)]
 pub enum MoveType {
     Move,
     Capture,
+    /// A piece placed on the board from the pocket of the player
+    Drop,
+}
+
+impl Turn {
+    /// Returns the other player
+    pub fn opponent(&self) -> Turn {
+        match self {
+            Turn::White => Turn::Black,
+            Turn::Black => Turn::White,
+        }
+    }
 }
 
 /// A sturuct containing each square in a game of chess
@@ -54,12 +99,26 @@ impl<T> Board<T> {
     }
 }
 
+impl Board<Color<Piece>> {
+    /// Returns the position of the king of a player if it is on the board
+    pub fn find_king(&self, player_color: &Turn) -> Option<BoardPosition> {
+        self.iter()
+            .zip(crate::position::iter())
+            .find(|(piece, _)| {
+                matches!(piece, Some(piece) if piece.same_color(player_color)
+                    && matches!(piece.get_internal(), Piece::King { .. }))
+            })
+            .map(|(_, position)| position)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::position::{File::*, Rank::*};
     use crate::ChessGame;
-    use crate::is_in_check;
+    use crate::board::check::is_in_check;
     use crate::piece::shorthands::*;
 
     #[test]
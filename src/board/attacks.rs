@@ -0,0 +1,138 @@
+use crate::board::check::get_attackers;
+use crate::board::{Board, Turn};
+use crate::position::{self, BoardPosition};
+use crate::ChessGame;
+
+/// The pieces attacking each square for both players.
+///
+/// A piece attacks the squares it could capture on whatever is on them, so the attacks of a
+/// player on its own pieces are the defenders of the pieces. Only the first piece on a line is
+/// counted, a queen behind a rook attacks nothing along the line of the rook.
+///
+/// ```rust
+/// use viktoe_chess::prelude::*;
+/// use viktoe_chess::board::Turn;
+///
+/// let game = ChessGame::default();
+/// let attacks = game.get_attack_map();
+///
+/// // The pawns on e2 and g2 and the knight on g1 attack f3
+/// assert_eq!(attacks.get_attackers(&(F, Three).into(), &Turn::White).len(), 3);
+/// assert_eq!(attacks.get_heatmap(&Turn::White).get(&(F, Three).into()), &Some(3));
+/// assert_eq!(attacks.get_heatmap(&Turn::Black).get(&(F, Three).into()), &None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttackMap {
+    white: Board<Vec<BoardPosition>>,
+    black: Board<Vec<BoardPosition>>,
+}
+
+impl AttackMap {
+    /// Returns the squares of the pieces of a player attacking a square
+    pub fn get_attackers(&self, position: &BoardPosition, player_color: &Turn) -> &[BoardPosition] {
+        self.get_board(player_color).get(position).as_deref().unwrap_or_default()
+    }
+
+    /// Returns the number of pieces of a player attacking each square, None for the squares the
+    /// player does not attack
+    pub fn get_heatmap(&self, player_color: &Turn) -> Board<u8> {
+        let mut heatmap = Board::default();
+
+        for (attackers, index) in self.get_board(player_color).iter().zip(0..) {
+            heatmap.board[index] = attackers.as_ref().map(|attackers| attackers.len() as u8);
+        }
+
+        heatmap
+    }
+
+    /// Returns the number of attacks of white minus the number of attacks of black on each
+    /// square, None for the squares neither player attacks
+    pub fn get_control(&self) -> Board<i8> {
+        let white = self.get_heatmap(&Turn::White);
+        let black = self.get_heatmap(&Turn::Black);
+        let mut control = Board::default();
+
+        for (index, (white, black)) in white.iter().zip(black.iter()).enumerate() {
+            if white.is_some() || black.is_some() {
+                control.board[index] = Some(white.unwrap_or(0) as i8 - black.unwrap_or(0) as i8);
+            }
+        }
+
+        control
+    }
+
+    fn get_board(&self, player_color: &Turn) -> &Board<Vec<BoardPosition>> {
+        match player_color {
+            Turn::White => &self.white,
+            Turn::Black => &self.black,
+        }
+    }
+}
+
+impl ChessGame {
+    /// Returns the squares of the pieces of a player attacking a square, the pieces of the player
+    /// on the square are defended by them. See [`AttackMap`].
+    pub fn get_attackers(&self, position: &BoardPosition, player_color: &Turn) -> Vec<BoardPosition> {
+        get_attackers(&self.board, position, player_color)
+    }
+
+    /// Returns the pieces attacking each square for both players
+    pub fn get_attack_map(&self) -> AttackMap {
+        let mut attacks = AttackMap {
+            white: Board::default(),
+            black: Board::default(),
+        };
+
+        for position in position::iter() {
+            for player_color in [Turn::White, Turn::Black] {
+                let attackers = get_attackers(&self.board, &position, &player_color);
+
+                if !attackers.is_empty() {
+                    let board = match player_color {
+                        Turn::White => &mut attacks.white,
+                        Turn::Black => &mut attacks.black,
+                    };
+
+                    board.set(&position, Some(attackers));
+                }
+            }
+        }
+
+        attacks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Turn;
+    use crate::prelude::*;
+
+    #[test]
+    fn maps_attacks_and_defences() {
+        let game = ChessGame::from_fen(String::from("4k3/8/8/3r4/8/3P4/8/3QK2R w - - 0 1")).unwrap();
+        let attacks = game.get_attack_map();
+
+        // The rook on d5 attacks the pawn on d3, which is defended by the queen and blocks both
+        assert_eq!(attacks.get_attackers(&(D, Three).into(), &Turn::Black), [BoardPosition::from((D, Five))]);
+        assert_eq!(attacks.get_attackers(&(D, Three).into(), &Turn::White), [BoardPosition::from((D, One))]);
+        assert!(attacks.get_attackers(&(D, Two).into(), &Turn::Black).is_empty());
+        assert!(attacks.get_attackers(&(D, Four).into(), &Turn::White).is_empty());
+
+        // The queen and the king defend each other
+        assert_eq!(attacks.get_attackers(&(D, One).into(), &Turn::White), [BoardPosition::from((E, One))]);
+        assert_eq!(attacks.get_attackers(&(E, One).into(), &Turn::White).len(), 2);
+        assert_eq!(game.get_attackers(&(E, One).into(), &Turn::White), attacks.get_attackers(&(E, One).into(), &Turn::White));
+
+        let heatmap = attacks.get_heatmap(&Turn::White);
+
+        assert_eq!(heatmap.get(&(E, Four).into()), &Some(1));
+        assert_eq!(heatmap.get(&(H, Eight).into()), &Some(1));
+        assert_eq!(heatmap.get(&(A, Eight).into()), &None);
+
+        let control = attacks.get_control();
+
+        assert_eq!(control.get(&(D, Four).into()), &Some(-1));
+        assert_eq!(control.get(&(E, Two).into()), &Some(2));
+        assert_eq!(control.get(&(A, Six).into()), &None);
+    }
+}
@@ -1,7 +1,8 @@
-use crate::board::{Board, MoveType, Turn};
+use crate::board::{Board, MoveType, Pin, Turn};
 use crate::position::BoardPosition;
 use crate::piece::shorthands::*;
 use crate::piece::{Piece, Color, StepCount, evaluate_vector};
+use std::ops::Range;
 
 pub(crate) fn is_in_check(
     board: &Board<Color<Piece>>,
@@ -23,7 +24,7 @@ pub(crate) fn is_in_check(
     }
 
     for base_vector in WHITE_KING.get_movement_base_vector() {
-        match evaluate_vector(board, base_vector, 0..2, player_color, king_position).last() {
+        match evaluate_vector(board, base_vector, StepCount::One.into(), player_color, king_position).last() {
             Some((position, MoveType::Capture))
                 if matches!(
                     board.get(position).as_ref().unwrap().get_internal(),
@@ -39,31 +40,138 @@ pub(crate) fn is_in_check(
     let move_sets = vec![
         (
             WHITE_ROOK.get_movement_base_vector(),
+            StepCount::Infinty,
             vec![Piece::Rook, Piece::Queen],
         ),
         (
             WHITE_BISHOP.get_movement_base_vector(),
+            StepCount::Infinty,
             vec![Piece::Bishop, Piece::Queen],
         ),
-        (WHITE_KNIGHT.get_movement_base_vector(), vec![Piece::Knight]),
+        (
+            WHITE_KNIGHT.get_movement_base_vector(),
+            StepCount::One,
+            vec![Piece::Knight],
+        ),
     ];
 
-    // Check if queen, rook, or bishop causes king to be in check
-    move_sets.into_iter().any(|(move_set, pieces)| {
-        check_vector(board, king_position, move_set, pieces, player_color)
+    // Check if queen, rook, bishop or knight causes king to be in check
+    move_sets.into_iter().any(|(move_set, number_of_steps, pieces)| {
+        check_vector(board, king_position, move_set, number_of_steps, pieces, player_color)
     })
 }
 
+// The directions of a kind of movement, how far it reaches and the pieces moving that way
+type MoveSet = (Vec<(i8, i8)>, StepCount, fn(&Piece) -> bool);
+
+// Returns the squares of the pieces of a player attacking a square, whatever is on the square.
+// The pieces are found by walking outwards from the square until the first piece on each ray.
+pub(crate) fn get_attackers(
+    board: &Board<Color<Piece>>,
+    position: &BoardPosition,
+    attacker_color: &Turn,
+) -> Vec<BoardPosition> {
+    // The rays stop at the pieces of the other player
+    let (player_color, pawn_vectors) = match attacker_color {
+        Turn::White => (Turn::Black, [(-1, -1), (1, -1)]),
+        Turn::Black => (Turn::White, [(-1, 1), (1, 1)]),
+    };
+
+    let move_sets: [MoveSet; 4] = [
+        (WHITE_KING.get_movement_base_vector(), StepCount::One, |piece| matches!(piece, Piece::King { .. })),
+        (WHITE_ROOK.get_movement_base_vector(), StepCount::Infinty, |piece| matches!(piece, Piece::Rook | Piece::Queen)),
+        (WHITE_BISHOP.get_movement_base_vector(), StepCount::Infinty, |piece| matches!(piece, Piece::Bishop | Piece::Queen)),
+        (WHITE_KNIGHT.get_movement_base_vector(), StepCount::One, |piece| matches!(piece, Piece::Knight)),
+    ];
+
+    let mut attackers: Vec<BoardPosition> = pawn_vectors
+        .into_iter()
+        .filter_map(|vector| position.add(vector).ok())
+        .filter(|position| match board.get(position) {
+            Some(piece) => piece.same_color(attacker_color) && matches!(piece.get_internal(), Piece::Pawn { .. }),
+            None => false,
+        })
+        .collect();
+
+    for (move_set, number_of_steps, is_attacker) in move_sets {
+        for base_vector in move_set {
+            let number_of_steps = number_of_steps.clone().into();
+
+            if let Some((position, MoveType::Capture)) =
+                evaluate_vector(board, base_vector, number_of_steps, &player_color, position).pop()
+            {
+                if board.get(&position).as_ref().is_some_and(|piece| is_attacker(piece.get_internal())) {
+                    attackers.push(position);
+                }
+            }
+        }
+    }
+
+    attackers
+}
+
+// Returns the first piece on each line from a square that has a rook, bishop or queen of a player
+// behind it moving along the line, whatever the colour of the piece in between. The ray of a pin
+// holds every square from the square up to and including the pinner.
+pub(crate) fn get_pins(
+    board: &Board<Color<Piece>>,
+    position: &BoardPosition,
+    pinner_color: &Turn,
+) -> Vec<Pin> {
+    let move_sets: [MoveSet; 2] = [
+        (WHITE_ROOK.get_movement_base_vector(), StepCount::Infinty, |piece| matches!(piece, Piece::Rook | Piece::Queen)),
+        (WHITE_BISHOP.get_movement_base_vector(), StepCount::Infinty, |piece| matches!(piece, Piece::Bishop | Piece::Queen)),
+    ];
+
+    let mut pins = Vec::new();
+
+    for (move_set, number_of_steps, is_pinner) in move_sets {
+        for base_vector in move_set {
+            let mut ray = Vec::new();
+            let mut blocker = None;
+
+            let number_of_steps: Range<i8> = number_of_steps.clone().into();
+
+            for step in number_of_steps {
+                let square = match position.add((base_vector.0 * step, base_vector.1 * step)) {
+                    Ok(square) => square,
+                    Err(_) => break,
+                };
+
+                ray.push(square.clone());
+
+                let piece = match board.get(&square) {
+                    Some(piece) => piece,
+                    None => continue,
+                };
+
+                match blocker {
+                    None => blocker = Some(square),
+                    Some(piece_position) => {
+                        if piece.same_color(pinner_color) && is_pinner(piece.get_internal()) {
+                            pins.push(Pin { piece: piece_position, pinner: square, ray });
+                        }
+
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    pins
+}
 
 fn check_vector(
     board: &Board<Color<Piece>>,
     position: &BoardPosition,
     move_set: Vec<(i8, i8)>,
+    number_of_steps: StepCount,
     pieces: Vec<Piece>,
     player_color: &Turn,
 ) -> bool {
     move_set.into_iter().any(|base_vector| {
-        let number_of_steps = StepCount::Infinty.into();
+        let number_of_steps = number_of_steps.clone().into();
 
         match evaluate_vector(board, base_vector, number_of_steps, player_color, position).last() {
             Some((position, MoveType::Capture)) => {
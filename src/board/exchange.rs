@@ -0,0 +1,161 @@
+use crate::board::check::get_attackers;
+use crate::board::{Board, Turn};
+use crate::chess_move::ChessMove;
+use crate::piece::{Color, Piece};
+use crate::position::BoardPosition;
+use crate::ChessGame;
+
+// The values of the pieces in an exchange in centipawns, the king is worth more than every other
+// piece together so it only captures last
+const PAWN: i32 = 100;
+const KNIGHT: i32 = 300;
+const BISHOP: i32 = 300;
+const ROOK: i32 = 500;
+const QUEEN: i32 = 900;
+const KING: i32 = 20_000;
+
+impl ChessGame {
+    /// Returns the material won by the player to move in centipawns when making the move and
+    /// then capturing back and forth on its square, with the least valuable piece first and each
+    /// player free to stop capturing. Sliders behind the capturing pieces join the exchange.
+    ///
+    /// The exchange counts a pawn as 100, a knight and a bishop as 300, a rook as 500 and a queen
+    /// as 900. Pins, promotions and the rules of variants are not taken into account. A quiet
+    /// move scores zero if the moved piece is safe on its square and less if it can be taken.
+    ///
+    /// ```rust
+    /// use viktoe_chess::prelude::*;
+    ///
+    /// // The pawn on d5 is defended by the pawn on e6
+    /// let game = ChessGame::from_fen(String::from("4k3/8/4p3/3p4/8/8/3Q4/4K3 w - - 0 1")).unwrap();
+    ///
+    /// assert_eq!(game.static_exchange(&"d2d5".parse().unwrap()), 100 - 900);
+    /// ```
+    pub fn static_exchange(&self, chess_move: &ChessMove) -> i32 {
+        let mut board = self.board.clone();
+
+        let (piece, to) = match chess_move {
+            ChessMove::Move { from, to, .. } => {
+                let piece = match board.get(from).clone() {
+                    Some(piece) => piece,
+                    None => return 0,
+                };
+
+                board.set(from, None);
+
+                // A pawn moving diagonally to an empty square captures by en passant, the captured
+                // pawn is put on the square to count it like any other capture
+                if matches!(piece.get_internal(), Piece::Pawn { .. })
+                    && board.get(to).is_none()
+                    && from.get_file() != to.get_file()
+                {
+                    let captured = BoardPosition::from((to.get_file().clone(), from.get_rank().clone()));
+                    let pawn = board.get(&captured).clone();
+
+                    board.set(&captured, None);
+                    board.set(to, pawn);
+                }
+
+                (piece, to)
+            }
+            ChessMove::Drop { piece, to } => match self.turn {
+                Turn::White => (Color::White(piece.clone()), to),
+                Turn::Black => (Color::Black(piece.clone()), to),
+            },
+        };
+
+        let gain = board.get(to).as_ref().map_or(0, |captured| exchange_value(captured.get_internal()));
+        let value = exchange_value(piece.get_internal());
+        board.set(to, Some(piece));
+
+        gain - exchange(&mut board, to, &self.turn.opponent(), value)
+    }
+
+    /// Returns true if the piece on a square can be won by the opponent of its owner, when the
+    /// opponent captures it with the least valuable attacker and the exchange on the square gains
+    /// material. See [`ChessGame::static_exchange`]. A king is never hanging.
+    pub fn is_hanging(&self, position: &BoardPosition) -> bool {
+        let owner = match self.board.get(position) {
+            Some(Color::White(Piece::King { .. }) | Color::Black(Piece::King { .. })) | None => return false,
+            Some(Color::White(_)) => Turn::White,
+            Some(Color::Black(_)) => Turn::Black,
+        };
+
+        let mut board = self.board.clone();
+        let value = board.get(position).as_ref().map_or(0, |piece| exchange_value(piece.get_internal()));
+
+        exchange(&mut board, position, &owner.opponent(), value) > 0
+    }
+}
+
+// Returns the material a player wins by capturing on a square holding a piece worth `value`,
+// the player can choose not to capture. The board is changed by the exchange.
+fn exchange(board: &mut Board<Color<Piece>>, position: &BoardPosition, player_color: &Turn, value: i32) -> i32 {
+    let attacker = get_attackers(board, position, player_color)
+        .into_iter()
+        .min_by_key(|attacker| board.get(attacker).as_ref().map_or(0, |piece| exchange_value(piece.get_internal())));
+
+    let attacker = match attacker {
+        Some(attacker) => attacker,
+        None => return 0,
+    };
+
+    let piece = board.get(&attacker).clone();
+    let piece_value = piece.as_ref().map_or(0, |piece| exchange_value(piece.get_internal()));
+
+    board.set(&attacker, None);
+    board.set(position, piece);
+
+    (value - exchange(board, position, &player_color.opponent(), piece_value)).max(0)
+}
+
+pub(crate) fn exchange_value(piece: &Piece) -> i32 {
+    match piece {
+        Piece::Pawn { .. } => PAWN,
+        Piece::Knight => KNIGHT,
+        Piece::Bishop => BISHOP,
+        Piece::Rook => ROOK,
+        Piece::Queen => QUEEN,
+        Piece::King { .. } => KING,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn exchange(fen: &str, chess_move: &str) -> i32 {
+        let game = ChessGame::from_fen(String::from(fen)).unwrap();
+
+        game.static_exchange(&chess_move.parse().unwrap())
+    }
+
+    #[test]
+    fn resolves_exchanges() {
+        // An undefended pawn
+        assert_eq!(exchange("4k3/8/8/3p4/8/8/3R4/4K3 w - - 0 1", "d2d5"), 100);
+        // The rook behind the first rook joins the exchange
+        assert_eq!(exchange("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", "d2d5"), 100);
+        assert_eq!(exchange("3rk3/8/8/3p4/8/8/3R4/4K3 w - - 0 1", "d2d5"), -400);
+        // The knight is lost for two pawns, the queen recaptures behind it
+        assert_eq!(exchange("4k3/2p5/3p4/8/4N3/8/8/3QK3 w - - 0 1", "e4d6"), -100);
+        // A quiet move to an attacked square and a safe one
+        assert_eq!(exchange("4k3/8/2p5/8/8/8/8/3QK3 w - - 0 1", "d1d5"), -900);
+        assert_eq!(exchange("4k3/8/2p5/8/8/8/8/3QK3 w - - 0 1", "d1d4"), 0);
+        // En passant
+        assert_eq!(exchange("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), 100);
+    }
+
+    #[test]
+    fn finds_hanging_pieces() {
+        let game = ChessGame::from_fen(String::from("4k3/8/3p4/4N3/3P4/8/1r6/B3K3 w - - 0 1")).unwrap();
+
+        // The knight is defended but worth more than the attacking pawn
+        assert!(game.is_hanging(&(E, Five).into()));
+        assert!(!game.is_hanging(&(D, Four).into()));
+        assert!(!game.is_hanging(&(D, Six).into()));
+        assert!(game.is_hanging(&(B, Two).into()));
+        assert!(!game.is_hanging(&(E, One).into()));
+        assert!(!game.is_hanging(&(A, One).into()));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::board::check::get_pins;
+use crate::board::Turn;
+use crate::piece::{Color, Piece};
+use crate::position::{self, BoardPosition};
+use crate::ChessGame;
+
+/// A piece standing on a line between a rook, bishop or queen and a piece behind it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pin {
+    /// The square of the piece on the line
+    pub piece: BoardPosition,
+    /// The square of the rook, bishop or queen
+    pub pinner: BoardPosition,
+    /// The squares from the piece behind up to and including the pinner, a pinned piece can only
+    /// move along these squares
+    pub ray: Vec<BoardPosition>,
+}
+
+impl ChessGame {
+    /// Returns the pieces of a player that can not leave the line between their king and an
+    /// attacking rook, bishop or queen without putting the king in check
+    ///
+    /// ```rust
+    /// use viktoe_chess::prelude::*;
+    /// use viktoe_chess::board::Turn;
+    ///
+    /// // The bishop on b5 pins the knight on c6 to the king on e8
+    /// let game = ChessGame::from_fen(String::from("4k3/8/2n5/1B6/8/8/8/4K3 b - - 0 1")).unwrap();
+    /// let pins = game.get_pins(&Turn::Black);
+    ///
+    /// assert_eq!(pins[0].piece, BoardPosition::from((C, Six)));
+    /// assert_eq!(pins[0].pinner, BoardPosition::from((B, Five)));
+    /// assert_eq!(pins[0].ray.len(), 3);
+    /// ```
+    pub fn get_pins(&self, player_color: &Turn) -> Vec<Pin> {
+        match self.board.find_king(player_color) {
+            Some(king) => self.get_pins_to(&king, player_color),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the pieces of a player on the line between one of their queens and an attacking
+    /// rook or bishop, the queen is lost if the piece moves off the line. A queen does not pin
+    /// to a queen as they can be traded.
+    pub fn get_relative_pins(&self, player_color: &Turn) -> Vec<Pin> {
+        position::iter()
+            .into_iter()
+            .filter(|position| {
+                matches!(self.board.get(position), Some(piece) if piece.same_color(player_color)
+                    && matches!(piece.get_internal(), Piece::Queen))
+            })
+            .flat_map(|queen| self.get_pins_to(&queen, player_color))
+            .filter(|pin| !matches!(self.board.get(&pin.pinner), Some(Color::White(Piece::Queen) | Color::Black(Piece::Queen))))
+            .collect()
+    }
+
+    /// Returns the pieces of a player on the line between one of their rooks, bishops or queens
+    /// and the king of the opponent, moving the piece off the line gives a discovered check. The
+    /// pinner of each returned pin is the piece giving the check.
+    pub fn get_discovered_checks(&self, player_color: &Turn) -> Vec<Pin> {
+        let king = match self.board.find_king(&player_color.opponent()) {
+            Some(king) => king,
+            None => return Vec::new(),
+        };
+
+        get_pins(&self.board, &king, player_color)
+            .into_iter()
+            .filter(|pin| matches!(self.board.get(&pin.piece), Some(piece) if piece.same_color(player_color)))
+            .collect()
+    }
+
+    // The pins of the pieces of a player against their piece on a square
+    fn get_pins_to(&self, position: &BoardPosition, player_color: &Turn) -> Vec<Pin> {
+        get_pins(&self.board, position, &player_color.opponent())
+            .into_iter()
+            .filter(|pin| matches!(self.board.get(&pin.piece), Some(piece) if piece.same_color(player_color)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Turn;
+    use crate::prelude::*;
+
+    #[test]
+    fn finds_pins_and_discovered_checks() {
+        let game = ChessGame::from_fen(String::from("3q3k/8/3nrN2/8/8/4R3/1B6/3RK3 w - - 0 1")).unwrap();
+
+        // The rook on e3 is pinned to the king by the rook on e6
+        let pins = game.get_pins(&Turn::White);
+
+        assert_eq!(pins.len(), 1);
+        assert_eq!(pins[0].piece, BoardPosition::from((E, Three)));
+        assert_eq!(pins[0].pinner, BoardPosition::from((E, Six)));
+        assert_eq!(pins[0].ray.len(), 5);
+        assert!(game.get_pins(&Turn::Black).is_empty());
+
+        // The knight on d6 is pinned to the queen by the rook on d1
+        let pins = game.get_relative_pins(&Turn::Black);
+
+        assert_eq!(pins.len(), 1);
+        assert_eq!(pins[0].piece, BoardPosition::from((D, Six)));
+        assert_eq!(pins[0].pinner, BoardPosition::from((D, One)));
+        assert!(game.get_relative_pins(&Turn::White).is_empty());
+
+        // Moving the knight on f6 uncovers a check by the bishop on b2
+        let checks = game.get_discovered_checks(&Turn::White);
+
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].piece, BoardPosition::from((F, Six)));
+        assert_eq!(checks[0].pinner, BoardPosition::from((B, Two)));
+        assert!(game.get_discovered_checks(&Turn::Black).is_empty());
+    }
+}
@@ -1,63 +1,111 @@
-use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
 
-use crate::position::{BoardPosition, File::*, Rank::*};
+use crate::position::{BoardPosition, Rank::*};
 use crate::piece::shorthands::*;
-use crate::piece::{Color, Piece};
+use crate::piece::{Color, Piece, PawnState};
 use crate::board::{Turn, GameState, Board};
+use crate::variant::{Standard, Variant};
 use crate::{ChessError, ChessGame};
 
 impl ChessGame {
-    // does not handle check or check mate, castling and en pasant
+    /// Creates a game of standard chess from a FEN string
     pub fn from_fen(fen_string: String) -> Result<Self, ChessError> {
+        Self::from_fen_with_variant(fen_string, Standard)
+    }
+
+    /// Creates a game of a variant from a FEN string.
+    ///
+    /// The number of checks given can be appended as `+N+M`, where N is the number of checks
+    /// given by white and M the number given by black. Pockets are read from brackets after the
+    /// board and promoted pieces are marked with a `~`. Earlier positions are not known so
+    /// repeated positions are counted from the given position.
+    pub fn from_fen_with_variant(
+        fen_string: String,
+        variant: impl Variant + 'static,
+    ) -> Result<Self, ChessError> {
         let mut fen = fen_string.split_whitespace();
 
-        let board_string = fen.next().unwrap();
-        let turn_string = fen.next().unwrap();
-        let _castle_string = fen.next().unwrap();
-        let _en_passant_string = fen.next().unwrap();
-        let half_clock_string = fen.next().unwrap();
-        let full_move_string = fen.next().unwrap();
+        let mut next = || fen.next().ok_or(ChessError::IncorrectFenString);
+
+        let board_string = next()?;
+        let turn_string = next()?;
+        let castle_string = next()?;
+        let en_passant_string = next()?;
+        let half_clock_string = next()?;
+        let full_move_string = next()?;
+        let checks_string = next().ok();
+
+        // Variants with pockets append the pockets to the board as [QRBNPqrbnp]
+        let (board_string, pocket_string) = match board_string.split_once('[') {
+            Some((board_string, pocket_string)) => (
+                board_string,
+                pocket_string
+                    .strip_suffix(']')
+                    .ok_or(ChessError::IncorrectFenString)?,
+            ),
+            None => (board_string, ""),
+        };
 
         let mut board: Board<Color<Piece>> = Board::default();
 
-        let temp_board = &mut board.board.iter_mut();
+        let mut positions = crate::position::iter().into_iter();
 
-        let mut white_king_position = BoardPosition::from((E, One));
-        let mut black_king_position = BoardPosition::from((E, Eight));
+        let mut white_king_position = None;
+        let mut black_king_position = None;
 
-        for (i, char) in board_string.chars().enumerate() {
-            match char {
+        let mut promoted = Vec::new();
+        let mut last_position = None;
+
+        for char in board_string.chars() {
+            let piece = match char {
                 '1'..='8' => {
                     for _i in 0..(char.to_digit(10).unwrap()) {
-                        temp_board.next();
+                        positions.next().ok_or(ChessError::IncorrectFenString)?;
                     }
+                    continue;
                 }
-                'k' => {
-                    *temp_board.next().unwrap() = {
-                        black_king_position =
-                            BoardPosition::try_from(((i / 8) as u8, (i % 8) as u8)).unwrap();
-                        Some(BLACK_KING)
-                    }
+                '/' => continue,
+                // Marks the previous piece as a promoted pawn
+                '~' => {
+                    promoted.push(last_position.take().ok_or(ChessError::IncorrectFenString)?);
+                    continue;
                 }
-                'q' => *temp_board.next().unwrap() = Some(BLACK_QUEEN),
-                'b' => *temp_board.next().unwrap() = Some(BLACK_BISHOP),
-                'n' => *temp_board.next().unwrap() = Some(BLACK_KNIGHT),
-                'r' => *temp_board.next().unwrap() = Some(BLACK_ROOK),
-                'p' => *temp_board.next().unwrap() = Some(BLACK_PAWN),
-                'K' => {
-                    *temp_board.next().unwrap() = {
-                        white_king_position =
-                            BoardPosition::try_from(((i / 8) as u8, (i % 8) as u8)).unwrap();
-                        Some(WHITE_KING)
-                    }
+                _ => Color::try_from(char)?,
+            };
+
+            let position = positions.next().ok_or(ChessError::IncorrectFenString)?;
+
+            // Pawns on their starting rank can still move two squares, as can pawns on the first
+            // rank in horde
+            let piece = match (piece, position.get_rank()) {
+                (WHITE_PAWN, One | Two) => NEW_WHITE_PAWN,
+                (BLACK_PAWN, Seven | Eight) => NEW_BLACK_PAWN,
+                (WHITE_KING, _) => {
+                    white_king_position = Some(position.clone());
+                    WHITE_KING
                 }
-                'Q' => *temp_board.next().unwrap() = Some(WHITE_QUEEN),
-                'B' => *temp_board.next().unwrap() = Some(WHITE_BISHOP),
-                'N' => *temp_board.next().unwrap() = Some(WHITE_KNIGHT),
-                'R' => *temp_board.next().unwrap() = Some(WHITE_ROOK),
-                'P' => *temp_board.next().unwrap() = Some(WHITE_PAWN),
-                '/' => {},
-                _ => return Err(ChessError::IncorrectFenString),
+                (BLACK_KING, _) => {
+                    black_king_position = Some(position.clone());
+                    BLACK_KING
+                }
+                (piece, _) => piece,
+            };
+
+            board.set(&position, Some(piece));
+            last_position = Some(position);
+        }
+
+        let mut white_pocket = Vec::new();
+        let mut black_pocket = Vec::new();
+
+        for char in pocket_string.chars() {
+            match Color::try_from(char)? {
+                Color::White(Piece::King { .. }) | Color::Black(Piece::King { .. }) => {
+                    return Err(ChessError::IncorrectFenString)
+                }
+                Color::White(piece) => white_pocket.push(piece),
+                Color::Black(piece) => black_pocket.push(piece),
             }
         }
 
@@ -67,16 +115,79 @@ impl ChessGame {
             _ => return Err(ChessError::IncorrectFenString),
         };
 
-        let half_move = half_clock_string.parse().unwrap();
-        let full_move = full_move_string.parse().unwrap();
+        // Castling options are stored in the king as (king side, queen side)
+        let mut white_castling = (false, false);
+        let mut black_castling = (false, false);
 
-        let state = GameState::Ongoing;
+        for char in castle_string.chars() {
+            match char {
+                'K' => white_castling.0 = true,
+                'Q' => white_castling.1 = true,
+                'k' => black_castling.0 = true,
+                'q' => black_castling.1 = true,
+                '-' => {}
+                _ => return Err(ChessError::IncorrectFenString),
+            }
+        }
 
-        let en_passant = Vec::new();
+        for (king_position, castling_state) in [
+            (&white_king_position, white_castling),
+            (&black_king_position, black_castling),
+        ] {
+            let king_position = match king_position {
+                Some(king_position) => king_position,
+                None => continue,
+            };
+
+            if let Some(king @ Color::White(Piece::King { .. }) | king @ Color::Black(Piece::King { .. })) =
+                board.get_mut(king_position).as_mut()
+            {
+                king.change_internal(Piece::King {
+                    check_state: None,
+                    castling_state,
+                });
+            }
+        }
+
+        // The FEN string contains the square behind the pawn that can be taken
+        let mut en_passant = Vec::new();
+
+        if en_passant_string != "-" {
+            let target = en_passant_string.parse::<BoardPosition>()?;
+
+            let pawn_position = match target.get_rank() {
+                Three => target.add((0, 1))?,
+                Six => target.add((0, -1))?,
+                _ => return Err(ChessError::IncorrectFenString),
+            };
+
+            if let Some(pawn @ Color::White(Piece::Pawn { .. }) | pawn @ Color::Black(Piece::Pawn { .. })) =
+                board.get_mut(&pawn_position).as_mut()
+            {
+                pawn.change_internal(Piece::Pawn {
+                    state: PawnState::PosibleEnPassant,
+                });
+                en_passant.push(pawn_position);
+            }
+        }
+
+        let half_move = half_clock_string
+            .parse()
+            .map_err(|_| ChessError::IncorrectFenString)?;
+        let full_move = full_move_string
+            .parse()
+            .map_err(|_| ChessError::IncorrectFenString)?;
+
+        let (white_checks, black_checks) = match checks_string {
+            Some(checks_string) => parse_checks(checks_string)?,
+            None => (0, 0),
+        };
+
+        let state = GameState::Ongoing;
 
         // Cannot handle earlier positions
-        let white_possition_history = HashMap::new();
-        let black_possition_history = HashMap::new();
+        let white_possition_history = Arc::default();
+        let black_possition_history = Arc::default();
 
         Ok(Self {
             board,
@@ -86,19 +197,215 @@ impl ChessGame {
             black_king_position,
             half_move,
             full_move,
+            white_checks,
+            black_checks,
+            white_pocket,
+            black_pocket,
+            promoted,
             state,
             white_possition_history,
             black_possition_history,
+            moves: Arc::default(),
+            previous_positions: Arc::default(),
+            variant: Arc::new(variant),
         })
     }
+
+    /// Returns the FEN string of the current position.
+    ///
+    /// The number of checks given is appended as `+N+M` if the variant limits the number of
+    /// checks, and the pockets as `[QRBNPqrbnp]` after the board if the variant has pockets.
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+
+        for (i, (square, position)) in self.board.iter().zip(crate::position::iter()).enumerate() {
+            if i != 0 && i % 8 == 0 {
+                fen.push('/');
+            }
+
+            match square {
+                Some(piece) => {
+                    fen.push(char::from(piece));
+
+                    if self.variant.has_pockets() && self.promoted.contains(&position) {
+                        fen.push('~');
+                    }
+                }
+                None => match fen.pop() {
+                    Some(empty @ '1'..='7') => fen.push((empty as u8 + 1) as char),
+                    Some(char) => {
+                        fen.push(char);
+                        fen.push('1');
+                    }
+                    None => fen.push('1'),
+                },
+            }
+        }
+
+        if self.variant.has_pockets() {
+            let mut pocket: Vec<Color<Piece>> = self
+                .white_pocket
+                .iter()
+                .map(|piece| Color::White(piece.clone()))
+                .chain(self.black_pocket.iter().map(|piece| Color::Black(piece.clone())))
+                .collect();
+
+            pocket.sort_by_key(|piece| {
+                let color = match piece {
+                    Color::White(_) => 0,
+                    Color::Black(_) => 1,
+                };
+                let order = match piece.get_internal() {
+                    Piece::King { .. } => 0,
+                    Piece::Queen => 1,
+                    Piece::Rook => 2,
+                    Piece::Bishop => 3,
+                    Piece::Knight => 4,
+                    Piece::Pawn { .. } => 5,
+                };
+
+                (color, order)
+            });
+
+            fen.push('[');
+            fen.extend(pocket.iter().map(char::from));
+            fen.push(']');
+        }
+
+        fen.push(' ');
+        fen.push(match self.turn {
+            Turn::White => 'w',
+            Turn::Black => 'b',
+        });
+        fen.push(' ');
+
+        let mut castling = String::new();
+
+        for (player_color, king_side, queen_side) in [(Turn::White, 'K', 'Q'), (Turn::Black, 'k', 'q')] {
+            if let Some(Piece::King { castling_state, .. }) = self
+                .get_king_position(&player_color)
+                .and_then(|king_position| self.board.get(king_position).as_ref())
+                .map(|king| king.get_internal())
+            {
+                if castling_state.0 {
+                    castling.push(king_side);
+                }
+                if castling_state.1 {
+                    castling.push(queen_side);
+                }
+            }
+        }
+
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        fen.push_str(&castling);
+        fen.push(' ');
+
+        // The square behind the pawn that moved two squares
+        match self.en_passant.first().and_then(|pawn_position| match self.board.get(pawn_position) {
+            Some(Color::White(_)) => pawn_position.add((0, -1)).ok(),
+            Some(Color::Black(_)) => pawn_position.add((0, 1)).ok(),
+            None => None,
+        }) {
+            Some(target) => fen.push_str(&target.to_string()),
+            None => fen.push('-'),
+        }
+
+        write!(fen, " {} {}", self.half_move, self.full_move).unwrap();
+
+        if self.variant.check_limit().is_some() {
+            write!(fen, " +{}+{}", self.white_checks, self.black_checks).unwrap();
+        }
+
+        fen
+    }
+}
+
+// Parses the number of checks given by each player in the format +N+M
+fn parse_checks(checks_string: &str) -> Result<(u8, u8), ChessError> {
+    let mut checks = checks_string
+        .strip_prefix('+')
+        .ok_or(ChessError::IncorrectFenString)?
+        .split('+')
+        .map(|checks| checks.parse::<u8>().map_err(|_| ChessError::IncorrectFenString));
+
+    match (checks.next(), checks.next(), checks.next()) {
+        (Some(white_checks), Some(black_checks), None) => Ok((white_checks?, black_checks?)),
+        _ => Err(ChessError::IncorrectFenString),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::position::File::*;
+    use crate::variant::{Antichess, Crazyhouse, ThreeCheck};
 
     #[test]
     fn works() {
         ChessGame::from_fen(String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0")).unwrap();
     }
+
+    #[test]
+    fn round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w Kq - 12 40",
+            "8/5k2/8/8/8/8/1K6/8 b - - 0 70",
+        ];
+
+        for fen in fens {
+            assert_eq!(ChessGame::from_fen(String::from(fen)).unwrap().to_fen(), fen);
+        }
+
+        assert_eq!(ChessGame::default().to_fen(), fens[0]);
+    }
+
+    #[test]
+    fn king_position_and_pawn_state() {
+        let mut game = ChessGame::from_fen(String::from("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1")).unwrap();
+
+        assert_eq!(game.get_king_position(&Turn::White), Some(&BoardPosition::from((E, One))));
+        assert_eq!(game.get_king_position(&Turn::Black), Some(&BoardPosition::from((E, Eight))));
+
+        game.move_piece(&(E, Two).into(), &(E, Four).into()).unwrap();
+
+        assert_eq!(game.to_fen(), "4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1");
+    }
+
+    #[test]
+    fn check_counters() {
+        let fen = "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2 +1+2";
+
+        let game = ChessGame::from_fen_with_variant(String::from(fen), ThreeCheck).unwrap();
+
+        assert_eq!(game.get_checks_given(&Turn::White), 1);
+        assert_eq!(game.get_checks_given(&Turn::Black), 2);
+        assert_eq!(game.to_fen(), fen);
+
+        assert!(ChessGame::from_fen(String::from("8/8/8/8/8/8/8/8 w - - 0 1 +1")).is_err());
+    }
+
+    #[test]
+    fn promoted_pieces_are_only_marked_with_pockets() {
+        let fen = "8/4P3/8/8/8/8/8/k3K3 w - - 0 1";
+
+        let mut game = ChessGame::from_fen(String::from(fen)).unwrap();
+        game.make_move(&"e7e8q".parse().unwrap()).unwrap();
+
+        assert_eq!(game.to_fen(), "4Q3/8/8/8/8/8/8/k3K3 b - - 0 1");
+
+        let mut game = ChessGame::from_fen_with_variant(String::from(fen), Antichess).unwrap();
+        game.make_move(&"e7e8q".parse().unwrap()).unwrap();
+
+        assert!(!game.to_fen().contains('~'));
+
+        let mut game = ChessGame::from_fen_with_variant(String::from(fen), Crazyhouse).unwrap();
+        game.make_move(&"e7e8q".parse().unwrap()).unwrap();
+
+        assert!(game.to_fen().starts_with("4Q~3/"));
+    }
 }
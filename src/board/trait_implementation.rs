@@ -1,8 +1,8 @@
-use std::collections::HashMap;
 use std::slice::Iter;
 
 use crate::ChessGame;
-use crate::board::{Board, GameState, Turn};
+use crate::board::Board;
+use crate::variant::Standard;
 use crate::piece::{shorthands::*, Color, Piece};
 use crate::position::{BoardPosition, File::*, Rank::*, FILE};
 
@@ -17,6 +17,13 @@ impl<T> Default for Board<T> {
 /// Will return the initial state of a standard game of chess
 impl Default for ChessGame {
     fn default() -> Self {
+        ChessGame::new(Standard)
+    }
+}
+
+impl Board<Color<Piece>> {
+    /// Returns the board as it is set up at the start of a standard game of chess
+    pub fn standard_position() -> Self {
         let mut board = Board::default();
 
         board.set(&BoardPosition::from((A, One)), Some(WHITE_ROOK));
@@ -31,8 +38,6 @@ impl Default for ChessGame {
         board.set(&BoardPosition::from((D, One)), Some(WHITE_QUEEN));
         board.set(&BoardPosition::from((E, One)), Some(NEW_WHITE_KING));
 
-        let white_king_position = BoardPosition::from((E, One));
-
         for file in FILE.into_iter() {
             board.set(
                 &BoardPosition::from((file, Two)),
@@ -52,8 +57,6 @@ impl Default for ChessGame {
         board.set(&BoardPosition::from((D, Eight)), Some(BLACK_QUEEN));
         board.set(&BoardPosition::from((E, Eight)), Some(NEW_BLACK_KING));
 
-        let black_king_position = BoardPosition::from((E, Eight));
-
         for file in FILE.into_iter() {
             board.set(
                 &BoardPosition::from((file, Seven)),
@@ -61,18 +64,7 @@ impl Default for ChessGame {
             );
         }
 
-        Self {
-            board,
-            turn: Turn::White,
-            state: GameState::Ongoing,
-            white_king_position,
-            black_king_position,
-            en_passant: Vec::new(),
-            half_move: 0,
-            full_move: 0,
-            white_possition_history: HashMap::new(),
-            black_possition_history: HashMap::new(),
-        }
+        board
     }
 }
 
@@ -0,0 +1,151 @@
+use crate::board::Turn;
+use crate::piece::{Color, PawnState, Piece};
+use crate::ChessGame;
+
+// Offsets of each kind of key in KEYS
+const PIECE_KEYS: usize = 0;
+const TURN_KEY: usize = PIECE_KEYS + 12 * 64;
+const CASTLING_KEYS: usize = TURN_KEY + 1;
+const EN_PASSANT_KEYS: usize = CASTLING_KEYS + 4;
+const POCKET_KEYS: usize = EN_PASSANT_KEYS + 8;
+const CHECK_KEYS: usize = POCKET_KEYS + 2 * 5 * POCKET_SIZE;
+const NUMBER_OF_KEYS: usize = CHECK_KEYS + 2 * 8;
+
+// The largest number of one kind of piece that is hashed in a pocket
+const POCKET_SIZE: usize = 16;
+
+// Random keys generated at compile time with splitmix64, so the hashes are the same on every run
+const KEYS: [u64; NUMBER_OF_KEYS] = generate_keys(0x7669_6b74_6f65_6368);
+
+const fn generate_keys(seed: u64) -> [u64; NUMBER_OF_KEYS] {
+    let mut keys = [0; NUMBER_OF_KEYS];
+    let mut state = seed;
+    let mut index = 0;
+
+    while index < NUMBER_OF_KEYS {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut key = state;
+        key = (key ^ (key >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        key = (key ^ (key >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[index] = key ^ (key >> 31);
+
+        index += 1;
+    }
+
+    keys
+}
+
+impl ChessGame {
+    /// Returns a Zobrist hash of the position, games in the same position have the same hash.
+    ///
+    /// The hash covers the pieces, the player whos turn it is, castling rights, en passant,
+    /// pockets and the checks given, but not the move counters or earlier positions.
+    pub fn get_hash(&self) -> u64 {
+        let mut hash = 0;
+
+        for (index, piece) in self.board.iter().enumerate() {
+            if let Some(piece) = piece {
+                hash ^= KEYS[PIECE_KEYS + piece_index(piece) * 64 + index];
+
+                if let Piece::King { castling_state, .. } = piece.get_internal() {
+                    let offset = match piece {
+                        Color::White(_) => CASTLING_KEYS,
+                        Color::Black(_) => CASTLING_KEYS + 2,
+                    };
+
+                    if castling_state.0 {
+                        hash ^= KEYS[offset];
+                    }
+                    if castling_state.1 {
+                        hash ^= KEYS[offset + 1];
+                    }
+                }
+            }
+        }
+
+        if matches!(self.turn, Turn::Black) {
+            hash ^= KEYS[TURN_KEY];
+        }
+
+        for pawn_position in &self.en_passant {
+            hash ^= KEYS[EN_PASSANT_KEYS + usize::from(u8::from(pawn_position.get_file()))];
+        }
+
+        for (color_offset, pocket) in [(0, &self.white_pocket), (5, &self.black_pocket)] {
+            for (piece_offset, kind) in [
+                Piece::Pawn {
+                    state: PawnState::Default,
+                },
+                Piece::Knight,
+                Piece::Bishop,
+                Piece::Rook,
+                Piece::Queen,
+            ]
+            .iter()
+            .enumerate()
+            {
+                let count = pocket
+                    .iter()
+                    .filter(|piece| std::mem::discriminant(*piece) == std::mem::discriminant(kind))
+                    .count()
+                    .min(POCKET_SIZE);
+
+                if count > 0 {
+                    hash ^= KEYS[POCKET_KEYS + (color_offset + piece_offset) * POCKET_SIZE + count - 1];
+                }
+            }
+        }
+
+        for (offset, checks) in [(0, self.white_checks), (8, self.black_checks)] {
+            if checks > 0 {
+                hash ^= KEYS[CHECK_KEYS + offset + usize::from(checks.min(8)) - 1];
+            }
+        }
+
+        hash
+    }
+}
+
+fn piece_index(piece: &Color<Piece>) -> usize {
+    let kind = match piece.get_internal() {
+        Piece::Pawn { .. } => 0,
+        Piece::Knight => 1,
+        Piece::Bishop => 2,
+        Piece::Rook => 3,
+        Piece::Queen => 4,
+        Piece::King { .. } => 5,
+    };
+
+    match piece {
+        Color::White(_) => kind,
+        Color::Black(_) => kind + 6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::position::{File::*, Rank::*};
+    use crate::ChessGame;
+
+    #[test]
+    fn transpositions_have_the_same_hash() {
+        let mut game = ChessGame::default();
+        let start = game.get_hash();
+
+        game.move_piece(&(G, One).into(), &(F, Three).into()).unwrap();
+        assert_ne!(game.get_hash(), start);
+
+        game.move_piece(&(G, Eight).into(), &(F, Six).into()).unwrap();
+        game.move_piece(&(F, Three).into(), &(G, One).into()).unwrap();
+        game.move_piece(&(F, Six).into(), &(G, Eight).into()).unwrap();
+        assert_eq!(game.get_hash(), start);
+
+        let first = ChessGame::from_fen(String::from("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1")).unwrap();
+        let second = ChessGame::from_fen(String::from("4k3/8/8/8/8/8/8/R3K2R w K - 0 1")).unwrap();
+        let third = ChessGame::from_fen(String::from("4k3/8/8/8/8/8/8/R3K2R b KQ - 0 1")).unwrap();
+
+        assert_ne!(first.get_hash(), second.get_hash());
+        assert_ne!(first.get_hash(), third.get_hash());
+    }
+}
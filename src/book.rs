@@ -0,0 +1,231 @@
+//! Opening books in the Polyglot `.bin` format, read from a file or built from games with an
+//! [`OpeningTree`].
+//!
+//! ```rust,no_run
+//! use viktoe_chess::book::Book;
+//! use viktoe_chess::prelude::*;
+//!
+//! let book = Book::open("performance.bin").unwrap();
+//! let game = ChessGame::default();
+//!
+//! if let Some(chess_move) = book.get_best_move(&game) {
+//!     println!("{chess_move}");
+//! }
+//! ```
+
+mod builder;
+mod polyglot;
+mod random;
+
+pub use builder::{MoveStatistics, OpeningTree, TreeOptions};
+
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::BuildHasher;
+use std::io;
+use std::path::Path;
+
+use crate::chess_move::ChessMove;
+use crate::ChessGame;
+
+// The size of an entry in a Polyglot file
+const ENTRY_SIZE: usize = 16;
+
+/// An entry of a Polyglot book, a move played in the position with the key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookEntry {
+    /// The key of the position, see [`ChessGame::get_polyglot_key`]
+    pub key: u64,
+    /// The move in the encoding of Polyglot
+    pub raw_move: u16,
+    /// How often the move should be played compared to the other moves of the position
+    pub weight: u16,
+    pub learn: u32,
+}
+
+impl BookEntry {
+    /// Creates an entry for a move in a position, or None if Polyglot can not encode the move
+    pub fn new(game: &ChessGame, chess_move: &ChessMove, weight: u16) -> Option<Self> {
+        Some(Self {
+            key: game.get_polyglot_key(),
+            raw_move: polyglot::encode_move(game, chess_move)?,
+            weight,
+            learn: 0,
+        })
+    }
+}
+
+/// A move found in a book with its weight
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookMove {
+    pub chess_move: ChessMove,
+    pub weight: u16,
+}
+
+/// An opening book with its entries sorted by key
+#[derive(Debug, Clone, Default)]
+pub struct Book {
+    entries: Vec<BookEntry>,
+}
+
+impl Book {
+    /// Reads a Polyglot book from a file
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::from_bytes(&fs::read(path)?))
+    }
+
+    /// Reads a Polyglot book from its bytes, an incomplete entry at the end is ignored
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let entries = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|entry| BookEntry {
+                key: u64::from_be_bytes(entry[0..8].try_into().expect("The slice has 8 bytes")),
+                raw_move: u16::from_be_bytes([entry[8], entry[9]]),
+                weight: u16::from_be_bytes([entry[10], entry[11]]),
+                learn: u32::from_be_bytes(entry[12..16].try_into().expect("The slice has 4 bytes")),
+            })
+            .collect();
+
+        // Books should be sorted already, in which case this is cheap
+        Self::from_entries(entries)
+    }
+
+    /// Creates a book from entries in any order
+    pub fn from_entries(mut entries: Vec<BookEntry>) -> Self {
+        entries.sort_by_key(|entry| entry.key);
+
+        Self { entries }
+    }
+
+    /// Returns the book in the Polyglot format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.entries.len() * ENTRY_SIZE);
+
+        for entry in &self.entries {
+            bytes.extend(entry.key.to_be_bytes());
+            bytes.extend(entry.raw_move.to_be_bytes());
+            bytes.extend(entry.weight.to_be_bytes());
+            bytes.extend(entry.learn.to_be_bytes());
+        }
+
+        bytes
+    }
+
+    /// Returns every entry of the book
+    pub fn get_entries(&self) -> &[BookEntry] {
+        &self.entries
+    }
+
+    /// Returns the entries of a position
+    pub fn get_position_entries(&self, game: &ChessGame) -> &[BookEntry] {
+        let key = game.get_polyglot_key();
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = self.entries.partition_point(|entry| entry.key <= key);
+
+        &self.entries[start..end]
+    }
+
+    /// Returns the legal moves of the book in a position, ordered by weight from high to low
+    pub fn get_moves(&self, game: &ChessGame) -> Vec<BookMove> {
+        let legal_moves = game.get_legal_moves();
+
+        let mut moves: Vec<BookMove> = self
+            .get_position_entries(game)
+            .iter()
+            .filter_map(|entry| {
+                Some(BookMove {
+                    chess_move: polyglot::decode_move(game, entry.raw_move)?,
+                    weight: entry.weight,
+                })
+            })
+            .filter(|book_move| legal_moves.contains(&book_move.chess_move))
+            .collect();
+
+        moves.sort_by_key(|book_move| std::cmp::Reverse(book_move.weight));
+
+        moves
+    }
+
+    /// Returns the move with the highest weight in a position
+    pub fn get_best_move(&self, game: &ChessGame) -> Option<ChessMove> {
+        self.get_moves(game)
+            .into_iter()
+            .next()
+            .map(|book_move| book_move.chess_move)
+    }
+
+    /// Picks a move with a chance proportional to its weight, using `random` as the source of
+    /// randomness so the choice can be repeated
+    pub fn get_weighted_move(&self, game: &ChessGame, random: u64) -> Option<ChessMove> {
+        let moves = self.get_moves(game);
+        let total: u64 = moves.iter().map(|book_move| u64::from(book_move.weight)).sum();
+
+        if total == 0 {
+            return moves.into_iter().next().map(|book_move| book_move.chess_move);
+        }
+
+        let mut target = random % total;
+
+        moves
+            .into_iter()
+            .find(|book_move| match target.checked_sub(u64::from(book_move.weight)) {
+                Some(remaining) => {
+                    target = remaining;
+                    false
+                }
+                None => true,
+            })
+            .map(|book_move| book_move.chess_move)
+    }
+
+    /// Picks a move with a chance proportional to its weight
+    pub fn get_random_move(&self, game: &ChessGame) -> Option<ChessMove> {
+        // The hasher is seeded randomly by the standard library
+        let random = RandomState::new().hash_one(game.get_polyglot_key());
+
+        self.get_weighted_move(game, random)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(game: &ChessGame, chess_move: &str, weight: u16) -> BookEntry {
+        BookEntry::new(game, &chess_move.parse().unwrap(), weight).unwrap()
+    }
+
+    #[test]
+    fn finds_the_moves_of_a_position() {
+        let start = ChessGame::default();
+        let mut after_e4 = ChessGame::default();
+        after_e4.make_move(&"e2e4".parse().unwrap()).unwrap();
+
+        let book = Book::from_entries(vec![
+            entry(&after_e4, "c7c5", 3),
+            entry(&start, "d2d4", 1),
+            entry(&start, "e2e4", 3),
+            entry(&after_e4, "e7e5", 2),
+        ]);
+
+        // Reading the bytes back gives the same book
+        let book = Book::from_bytes(&book.to_bytes());
+
+        assert_eq!(book.get_entries().len(), 4);
+        assert_eq!(book.get_position_entries(&start).len(), 2);
+
+        let moves = book.get_moves(&after_e4);
+
+        assert_eq!(moves[0].chess_move, "c7c5".parse().unwrap());
+        assert_eq!(moves[1].weight, 2);
+
+        assert_eq!(book.get_best_move(&start), Some("e2e4".parse().unwrap()));
+        assert_eq!(book.get_weighted_move(&start, 2), Some("e2e4".parse().unwrap()));
+        assert_eq!(book.get_weighted_move(&start, 3), Some("d2d4".parse().unwrap()));
+        assert!(book.get_random_move(&start).is_some());
+
+        after_e4.make_move(&"e7e5".parse().unwrap()).unwrap();
+
+        assert_eq!(book.get_best_move(&after_e4), None);
+    }
+}
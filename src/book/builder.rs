@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use super::{polyglot, Book, BookEntry};
+use crate::board::Turn;
+use crate::chess_move::ChessMove;
+use crate::pgn::{self, PgnGame};
+use crate::{ChessError, ChessGame};
+
+/// Which moves of the games are added to an [`OpeningTree`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeOptions {
+    /// The number of plies from the start of each game that are added
+    pub max_depth: usize,
+    /// Moves played in fewer games are left out of books made from the tree
+    pub min_games: u32,
+}
+
+impl Default for TreeOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 24,
+            min_games: 1,
+        }
+    }
+}
+
+/// How often a move was played and how the games ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveStatistics {
+    pub chess_move: ChessMove,
+    /// The player making the move
+    pub player: Turn,
+    pub games: u32,
+    pub white_wins: u32,
+    pub draws: u32,
+    pub black_wins: u32,
+    raw_move: Option<u16>,
+}
+
+impl MoveStatistics {
+    /// Returns the score of the move for the player making it, two points for a win and one for
+    /// a draw, as Polyglot weights book moves
+    pub fn get_score(&self) -> u32 {
+        let wins = match self.player {
+            Turn::White => self.white_wins,
+            Turn::Black => self.black_wins,
+        };
+
+        wins * 2 + self.draws
+    }
+}
+
+/// The moves played in a collection of games, keyed by the Polyglot key of the position.
+///
+/// ```rust
+/// use viktoe_chess::book::{OpeningTree, TreeOptions};
+/// use viktoe_chess::prelude::*;
+///
+/// let mut tree = OpeningTree::new(TreeOptions::default());
+/// tree.add_pgn("1. e4 e5 2. Nf3 1-0\n\n1. e4 c5 1/2-1/2\n\n1. d4 d5 0-1");
+///
+/// let moves = tree.get_moves(&ChessGame::default());
+///
+/// assert_eq!(moves[0].chess_move, "e2e4".parse().unwrap());
+/// assert_eq!((moves[0].games, moves[0].white_wins, moves[0].draws), (2, 1, 1));
+///
+/// let book = tree.to_book();
+///
+/// assert_eq!(book.get_best_move(&ChessGame::default()), Some("e2e4".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OpeningTree {
+    options: TreeOptions,
+    positions: HashMap<u64, Vec<MoveStatistics>>,
+}
+
+impl OpeningTree {
+    /// Creates an empty tree
+    pub fn new(options: TreeOptions) -> Self {
+        Self {
+            options,
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Adds the moves of a game up to the depth limit.
+    ///
+    /// Fails at the first move that can not be played, the moves before it stay in the tree.
+    pub fn add_game(&mut self, pgn_game: &PgnGame) -> Result<(), ChessError> {
+        let mut game = pgn_game.get_start_position()?;
+
+        let winner = match pgn_game.result.as_str() {
+            "1-0" => Some(Some(Turn::White)),
+            "0-1" => Some(Some(Turn::Black)),
+            "1/2-1/2" => Some(None),
+            _ => None,
+        };
+
+        for san in pgn_game.moves.iter().take(self.options.max_depth) {
+            let chess_move = game.parse_san(san)?;
+
+            let moves = self.positions.entry(game.get_polyglot_key()).or_default();
+
+            let index = match moves.iter().position(|statistics| statistics.chess_move == chess_move) {
+                Some(index) => index,
+                None => {
+                    moves.push(MoveStatistics {
+                        raw_move: polyglot::encode_move(&game, &chess_move),
+                        chess_move: chess_move.clone(),
+                        player: game.get_player_turn().clone(),
+                        games: 0,
+                        white_wins: 0,
+                        draws: 0,
+                        black_wins: 0,
+                    });
+
+                    moves.len() - 1
+                }
+            };
+
+            let statistics = &mut moves[index];
+            statistics.games += 1;
+
+            match winner {
+                Some(Some(Turn::White)) => statistics.white_wins += 1,
+                Some(Some(Turn::Black)) => statistics.black_wins += 1,
+                Some(None) => statistics.draws += 1,
+                None => {}
+            }
+
+            // The move was found among the legal moves by parse_san
+            game.play_unchecked(&chess_move);
+        }
+
+        Ok(())
+    }
+
+    /// Adds every game of a PGN file, returning the number of games that could be read without
+    /// an illegal move
+    pub fn add_pgn(&mut self, text: &str) -> usize {
+        pgn::parse(text)
+            .iter()
+            .filter(|pgn_game| self.add_game(pgn_game).is_ok())
+            .count()
+    }
+
+    /// Returns the moves played in a position, the most played first
+    pub fn get_moves(&self, game: &ChessGame) -> Vec<&MoveStatistics> {
+        let mut moves: Vec<&MoveStatistics> = self
+            .positions
+            .get(&game.get_polyglot_key())
+            .map(|moves| moves.iter().collect())
+            .unwrap_or_default();
+
+        moves.sort_by_key(|statistics| std::cmp::Reverse(statistics.games));
+
+        moves
+    }
+
+    /// Returns the number of positions in the tree
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Creates a Polyglot book of the moves played in at least the minimum number of games,
+    /// weighted by [`MoveStatistics::get_score`]
+    pub fn to_book(&self) -> Book {
+        let mut entries = Vec::new();
+
+        for (key, moves) in &self.positions {
+            let moves: Vec<&MoveStatistics> = moves
+                .iter()
+                .filter(|statistics| statistics.games >= self.options.min_games)
+                .collect();
+
+            // Weights only matter compared to the other moves of the position, so they are scaled
+            // down if the largest does not fit
+            let largest = moves.iter().map(|statistics| statistics.get_score()).max().unwrap_or_default();
+            let scale = u64::from(largest).div_ceil(u64::from(u16::MAX)).max(1);
+
+            for statistics in moves {
+                if let Some(raw_move) = statistics.raw_move {
+                    entries.push(BookEntry {
+                        key: *key,
+                        raw_move,
+                        weight: (u64::from(statistics.get_score()) / scale) as u16,
+                        learn: 0,
+                    });
+                }
+            }
+        }
+
+        Book::from_entries(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAMES: &str = "
+1. e4 e5 2. Nf3 Nc6 1-0
+1. e4 e5 2. Nf3 Nf6 0-1
+1. e4 c5 2. Nf3 1/2-1/2
+1. d4 d5 *
+1. e4 e5 2. Ke3 1-0
+";
+
+    #[test]
+    fn counts_moves_and_results() {
+        let mut tree = OpeningTree::new(TreeOptions::default());
+
+        assert_eq!(tree.add_pgn(GAMES), 4);
+
+        let mut game = ChessGame::default();
+        let moves = tree.get_moves(&game);
+
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].chess_move, "e2e4".parse().unwrap());
+        assert_eq!(
+            (moves[0].games, moves[0].white_wins, moves[0].draws, moves[0].black_wins),
+            (4, 2, 1, 1)
+        );
+        assert_eq!(moves[1].games, 1);
+        assert_eq!(moves[1].get_score(), 0);
+
+        // The illegal game added the moves before its illegal move
+        game.make_move(&"e2e4".parse().unwrap()).unwrap();
+        game.make_move(&"e7e5".parse().unwrap()).unwrap();
+
+        let moves = tree.get_moves(&game);
+
+        assert_eq!(moves[0].chess_move, "g1f3".parse().unwrap());
+        assert_eq!(moves[0].games, 2);
+        assert_eq!(moves[0].player, Turn::White);
+    }
+
+    #[test]
+    fn filters_by_depth_and_games() {
+        let mut tree = OpeningTree::new(TreeOptions {
+            max_depth: 2,
+            min_games: 2,
+        });
+
+        tree.add_pgn(GAMES);
+
+        // The start position and the positions after e4 and d4
+        assert_eq!(tree.len(), 3);
+
+        let book = tree.to_book();
+        let mut game = ChessGame::default();
+
+        assert_eq!(book.get_entries().len(), 2);
+        assert_eq!(book.get_moves(&game)[0].weight, 2 * 2 + 1);
+
+        game.make_move(&"e2e4".parse().unwrap()).unwrap();
+
+        assert_eq!(book.get_best_move(&game), Some("e7e5".parse().unwrap()));
+    }
+}
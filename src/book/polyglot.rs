@@ -0,0 +1,201 @@
+use super::random::RANDOM64;
+use crate::board::Turn;
+use crate::chess_move::ChessMove;
+use crate::piece::{Color, Piece};
+use crate::position::{BoardPosition, File::*, Rank::*};
+use crate::ChessGame;
+
+// Offsets of each kind of key in RANDOM64
+const CASTLING_KEYS: usize = 768;
+const EN_PASSANT_KEYS: usize = 772;
+const TURN_KEY: usize = 780;
+
+impl ChessGame {
+    /// Returns the key of the position in Polyglot opening books.
+    ///
+    /// Unlike [`ChessGame::get_hash`] the key only includes the en passant file if a pawn of the
+    /// player whos turn it is stands next to the pawn that can be taken, and it ignores pockets
+    /// and the checks given.
+    pub fn get_polyglot_key(&self) -> u64 {
+        let mut key = 0;
+
+        for (index, piece) in self.board.iter().enumerate() {
+            if let Some(piece) = piece {
+                // The board starts at a8, Polyglot squares start at a1
+                key ^= RANDOM64[64 * piece_kind(piece) + (index ^ 56)];
+
+                if let Piece::King { castling_state, .. } = piece.get_internal() {
+                    let offset = match piece {
+                        Color::White(_) => CASTLING_KEYS,
+                        Color::Black(_) => CASTLING_KEYS + 2,
+                    };
+
+                    if castling_state.0 {
+                        key ^= RANDOM64[offset];
+                    }
+                    if castling_state.1 {
+                        key ^= RANDOM64[offset + 1];
+                    }
+                }
+            }
+        }
+
+        for pawn_position in &self.en_passant {
+            let can_be_taken = [-1, 1].iter().any(|file_offset| {
+                match (pawn_position.add((*file_offset, 0)), &self.turn) {
+                    (Ok(position), Turn::White) => {
+                        matches!(self.board.get(&position), Some(Color::White(Piece::Pawn { .. })))
+                    }
+                    (Ok(position), Turn::Black) => {
+                        matches!(self.board.get(&position), Some(Color::Black(Piece::Pawn { .. })))
+                    }
+                    (Err(_), _) => false,
+                }
+            });
+
+            if can_be_taken {
+                key ^= RANDOM64[EN_PASSANT_KEYS + usize::from(u8::from(pawn_position.get_file()))];
+            }
+        }
+
+        if matches!(self.turn, Turn::White) {
+            key ^= RANDOM64[TURN_KEY];
+        }
+
+        key
+    }
+}
+
+// Polyglot orders the pieces black pawn, white pawn, black knight, white knight and so on
+fn piece_kind(piece: &Color<Piece>) -> usize {
+    let kind = match piece.get_internal() {
+        Piece::Pawn { .. } => 0,
+        Piece::Knight => 1,
+        Piece::Bishop => 2,
+        Piece::Rook => 3,
+        Piece::Queen => 4,
+        Piece::King { .. } => 5,
+    };
+
+    match piece {
+        Color::White(_) => kind * 2 + 1,
+        Color::Black(_) => kind * 2,
+    }
+}
+
+/// Decodes a move of a Polyglot book, castling is stored as the king taking its own rook
+pub(super) fn decode_move(game: &ChessGame, raw: u16) -> Option<ChessMove> {
+    let square = |bits: u16| BoardPosition::try_from(((bits & 7) as u8, ((bits >> 3) & 7) as u8)).ok();
+
+    let from = square(raw >> 6)?;
+    let mut to = square(raw)?;
+
+    let promotion = match (raw >> 12) & 7 {
+        0 => None,
+        1 => Some(Piece::Knight),
+        2 => Some(Piece::Bishop),
+        3 => Some(Piece::Rook),
+        4 => Some(Piece::Queen),
+        _ => return None,
+    };
+
+    if matches!(game.get_square(&from).as_ref().map(Color::get_internal), Some(Piece::King { .. }))
+        && matches!(from.get_file(), E)
+    {
+        match (to.get_file(), to.get_rank()) {
+            (H, rank @ (One | Eight)) => to = BoardPosition::from((G, rank.clone())),
+            (A, rank @ (One | Eight)) => to = BoardPosition::from((C, rank.clone())),
+            _ => {}
+        }
+    }
+
+    Some(ChessMove::Move {
+        from,
+        to,
+        promotion,
+    })
+}
+
+/// Encodes a move for a Polyglot book, the inverse of [`decode_move`]
+pub(super) fn encode_move(game: &ChessGame, chess_move: &ChessMove) -> Option<u16> {
+    let ChessMove::Move {
+        from,
+        to,
+        promotion,
+    } = chess_move
+    else {
+        return None;
+    };
+
+    let mut to = to.clone();
+
+    if matches!(game.get_square(from).as_ref().map(Color::get_internal), Some(Piece::King { .. }))
+        && matches!(from.get_file(), E)
+    {
+        match (to.get_file(), to.get_rank()) {
+            (G, rank @ (One | Eight)) => to = BoardPosition::from((H, rank.clone())),
+            (C, rank @ (One | Eight)) => to = BoardPosition::from((A, rank.clone())),
+            _ => {}
+        }
+    }
+
+    let square = |position: &BoardPosition| {
+        let (file, rank) = <(u8, u8)>::from(position);
+        u16::from(file) | u16::from(rank) << 3
+    };
+
+    let promotion = match promotion {
+        None => 0,
+        Some(Piece::Knight) => 1,
+        Some(Piece::Bishop) => 2,
+        Some(Piece::Rook) => 3,
+        Some(Piece::Queen) => 4,
+        Some(_) => return None,
+    };
+
+    Some(square(&to) | square(from) << 6 | promotion << 12)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_match_the_polyglot_reference() {
+        // Test positions from the Polyglot book format description
+        let positions = [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 0x463b_9618_1691_fc9c),
+            ("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1", 0x823c_9b50_fd11_4196),
+            ("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2", 0x0756_b944_61c5_0fb0),
+            ("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2", 0x662f_afb9_65db_29d4),
+            ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", 0x22a4_8b5a_8e47_ff78),
+            ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 0 3", 0x652a_607c_a3f2_42c1),
+            ("rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 0 4", 0x00fd_d303_c946_bdd9),
+            ("rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3", 0x3c81_23ea_7b06_7637),
+            ("rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 0 4", 0x5c3f_9b82_9b27_9560),
+        ];
+
+        for (fen, key) in positions {
+            assert_eq!(ChessGame::from_fen(fen.to_string()).unwrap().get_polyglot_key(), key, "{fen}");
+        }
+    }
+
+    #[test]
+    fn castling_is_encoded_as_taking_the_rook() {
+        let game = ChessGame::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1".to_string()).unwrap();
+
+        for (chess_move, raw) in [("e1g1", "e1h1"), ("e1c1", "e1a1"), ("e1f1", "e1f1")] {
+            let chess_move: ChessMove = chess_move.parse().unwrap();
+            let raw = encode_move(&game, &raw.parse().unwrap()).unwrap();
+
+            assert_eq!(encode_move(&game, &chess_move), Some(raw));
+            assert_eq!(decode_move(&game, raw), Some(chess_move));
+        }
+
+        let promotion: ChessMove = "a7a8q".parse().unwrap();
+        let raw = encode_move(&ChessGame::default(), &promotion).unwrap();
+
+        assert_eq!(raw, 0x4000 | 48 << 6 | 56);
+        assert_eq!(decode_move(&ChessGame::default(), raw), Some(promotion));
+    }
+}
@@ -0,0 +1,376 @@
+mod san;
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::{GameState, MoveType};
+use crate::piece::{Color, Piece};
+use crate::position::{self, BoardPosition, Rank::*};
+use crate::{ChessError, ChessGame};
+
+/// A complete move, including the piece a pawn is promoted to.
+///
+/// Formatted in the coordinate notation used by UCI, `e2e4` or `e7e8q` for moves and `P@e4` for
+/// drops.
+///
+/// ```rust
+/// use viktoe_chess::prelude::*;
+///
+/// let chess_move: ChessMove = "e2e4".parse().unwrap();
+///
+/// assert_eq!(chess_move, ChessMove::Move {
+///     from: BoardPosition::from((E, Two)),
+///     to: BoardPosition::from((E, Four)),
+///     promotion: None,
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ChessMove {
+    /// A piece moving from one square to another
+    Move {
+        from: BoardPosition,
+        to: BoardPosition,
+        promotion: Option<Piece>,
+    },
+    /// A piece from the pocket of the player placed on an empty square
+    Drop { piece: Piece, to: BoardPosition },
+}
+
+impl ChessMove {
+    /// Returns the square the piece ends up on
+    pub fn get_to(&self) -> &BoardPosition {
+        match self {
+            ChessMove::Move { to, .. } | ChessMove::Drop { to, .. } => to,
+        }
+    }
+}
+
+impl fmt::Display for ChessMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChessMove::Move {
+                from,
+                to,
+                promotion,
+            } => {
+                write!(f, "{from}{to}")?;
+
+                if let Some(piece) = promotion {
+                    write!(f, "{}", char::from(&Color::Black(piece.clone())))?;
+                }
+
+                Ok(())
+            }
+            ChessMove::Drop { piece, to } => {
+                write!(f, "{}@{to}", char::from(&Color::White(piece.clone())))
+            }
+        }
+    }
+}
+
+impl FromStr for ChessMove {
+    type Err = ChessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_ascii() {
+            return Err(ChessError::InvalidMove);
+        }
+
+        if let Some((piece, to)) = s.split_once('@') {
+            let mut chars = piece.chars();
+
+            let piece = match (chars.next(), chars.next()) {
+                (Some(char), None) => Color::try_from(char.to_ascii_uppercase())
+                    .map_err(|_| ChessError::InvalidMove)?,
+                _ => return Err(ChessError::InvalidMove),
+            };
+
+            return Ok(ChessMove::Drop {
+                piece: piece.get_internal().clone(),
+                to: to.parse()?,
+            });
+        }
+
+        if s.len() != 4 && s.len() != 5 {
+            return Err(ChessError::InvalidMove);
+        }
+
+        let promotion = match s[4..].chars().next() {
+            Some(char @ ('q' | 'r' | 'b' | 'n' | 'k')) => {
+                Some(Color::try_from(char)?.get_internal().clone())
+            }
+            Some(_) => return Err(ChessError::InvalidMove),
+            None => None,
+        };
+
+        Ok(ChessMove::Move {
+            from: s[0..2].parse()?,
+            to: s[2..4].parse()?,
+            promotion,
+        })
+    }
+}
+
+impl ChessGame {
+    /// Makes a move, including promoting the pawn or dropping a piece from the pocket.
+    ///
+    /// Fails with custom errors explaining why the move cannot be made, a move of a pawn to the
+    /// last rank needs a promotion and other moves can not have one.
+    pub fn make_move(&mut self, chess_move: &ChessMove) -> Result<GameState, ChessError> {
+        match chess_move {
+            ChessMove::Move {
+                from,
+                to,
+                promotion,
+            } => {
+                let is_promotion = matches!(
+                    self.get_square(from).as_ref().map(|piece| piece.get_internal()),
+                    Some(Piece::Pawn { .. })
+                ) && matches!(to.get_rank(), One | Eight);
+
+                if is_promotion != promotion.is_some() {
+                    return Err(ChessError::InvalidMove);
+                }
+
+                // The piece is checked before moving, so a rejected promotion leaves the game as
+                // it was
+                if promotion
+                    .as_ref()
+                    .is_some_and(|piece| !self.variant.promotion_pieces().contains(piece))
+                {
+                    return Err(ChessError::InvalidMove);
+                }
+
+                let state = self.move_piece(from, to)?;
+
+                match (state, promotion) {
+                    (GameState::Promotion(..), Some(piece)) => self.promote_pawn(piece.clone()),
+                    (state, _) => Ok(state),
+                }
+            }
+            ChessMove::Drop { piece, to } => self.drop_piece(piece.clone(), to),
+        }
+    }
+
+    // Makes a move known to be legal without validating it, used by the search where the moves
+    // come from get_legal_moves
+    pub(crate) fn play_unchecked(&mut self, chess_move: &ChessMove) {
+        match chess_move {
+            ChessMove::Move {
+                from,
+                to,
+                promotion,
+            } => {
+                let piece = match self.board.get(from).clone() {
+                    Some(piece) => piece,
+                    None => return,
+                };
+
+                // A pawn moving diagonally to an empty square captures by en passant
+                let move_type = if self.board.get(to).is_some()
+                    || (matches!(piece.get_internal(), Piece::Pawn { .. })
+                        && from.get_file() != to.get_file())
+                {
+                    MoveType::Capture
+                } else {
+                    MoveType::Move
+                };
+
+                self.perform_move(from, to, &move_type);
+
+                if let Some(promotion) = promotion {
+                    if let Some(pawn) = self.board.get_mut(to).as_mut() {
+                        pawn.change_internal(promotion.clone());
+                        self.promoted.push(to.clone());
+                    }
+                }
+
+                self.state = self.progress_turn(piece.get_internal(), &move_type);
+            }
+            ChessMove::Drop { piece, to } => {
+                self.perform_drop(piece, to);
+                self.state = self.progress_turn(piece, &MoveType::Drop);
+            }
+        }
+    }
+
+    /// Returns every move the player whos turn it is can make, with one move for each piece a
+    /// pawn can be promoted to.
+    pub fn get_legal_moves(&self) -> Vec<ChessMove> {
+        let mut moves = Vec::new();
+
+        for from in position::iter() {
+            let is_pawn = match self.get_square(&from) {
+                Some(piece) if piece.same_color(self.get_player_turn()) => {
+                    matches!(piece.get_internal(), Piece::Pawn { .. })
+                }
+                _ => continue,
+            };
+
+            for (move_type, to) in self.get_valid_moves(&from).iter().zip(position::iter()) {
+                if move_type.is_none() {
+                    continue;
+                }
+
+                if is_pawn && matches!(to.get_rank(), One | Eight) {
+                    for piece in self.variant.promotion_pieces() {
+                        moves.push(ChessMove::Move {
+                            from: from.clone(),
+                            to: to.clone(),
+                            promotion: Some(piece),
+                        });
+                    }
+                } else {
+                    moves.push(ChessMove::Move {
+                        from: from.clone(),
+                        to,
+                        promotion: None,
+                    });
+                }
+            }
+        }
+
+        let mut pocket: Vec<&Piece> = Vec::new();
+
+        for piece in self.get_pocket(self.get_player_turn()) {
+            if !pocket.contains(&piece) {
+                pocket.push(piece);
+            }
+        }
+
+        for piece in pocket {
+            for (move_type, to) in self.get_valid_drops(piece).iter().zip(position::iter()) {
+                if move_type.is_some() {
+                    moves.push(ChessMove::Drop {
+                        piece: piece.clone(),
+                        to,
+                    });
+                }
+            }
+        }
+
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::PawnState;
+    use crate::position::File::*;
+
+    #[test]
+    fn rejected_promotion_keeps_the_game() {
+        let fen = "8/4P3/8/8/8/8/k7/4K3 w - - 0 1";
+        let mut game = ChessGame::from_fen(String::from(fen)).unwrap();
+
+        assert!(game.make_move(&"e7e8k".parse().unwrap()).is_err());
+        assert_eq!(game.to_fen(), fen);
+        assert!(game.get_moves().is_empty());
+
+        game.make_move(&"e7e8q".parse().unwrap()).unwrap();
+
+        assert_eq!(game.to_fen(), "4Q3/8/8/8/8/8/k7/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn notation_round_trip() {
+        for notation in ["e2e4", "a7a8q", "h2h1n", "P@e4", "Q@d8"] {
+            assert_eq!(notation.parse::<ChessMove>().unwrap().to_string(), notation);
+        }
+    }
+
+    #[test]
+    fn parses_drops() {
+        assert_eq!(
+            "p@c3".parse::<ChessMove>().unwrap(),
+            ChessMove::Drop {
+                piece: Piece::Pawn {
+                    state: PawnState::Default
+                },
+                to: (C, Three).into(),
+            }
+        );
+    }
+
+    #[test]
+    fn undo_restores_the_position() {
+        let mut game = ChessGame::default();
+        let start = game.to_fen();
+
+        for chess_move in ["e2e4", "d7d5", "e4d5", "g8f6"] {
+            game.make_move(&chess_move.parse().unwrap()).unwrap();
+        }
+
+        let before = game.to_fen();
+
+        game.make_move(&"d5d6".parse().unwrap()).unwrap();
+
+        assert_eq!(game.undo_move().unwrap(), "d5d6".parse().unwrap());
+        assert_eq!(game.to_fen(), before);
+        assert_eq!(game.get_moves().len(), 4);
+
+        while game.undo_move().is_ok() {}
+
+        assert_eq!(game.to_fen(), start);
+        assert!(game.get_moves().is_empty());
+    }
+
+    #[test]
+    fn undo_forgets_repeated_positions() {
+        let mut game = ChessGame::default();
+
+        for _ in 0..2 {
+            for chess_move in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+                game.make_move(&chess_move.parse().unwrap()).unwrap();
+            }
+        }
+
+        game.make_move(&"g1f3".parse().unwrap()).unwrap();
+
+        assert!(game.request_draw_due_to_repeated_position());
+
+        game.undo_move().unwrap();
+
+        assert!(!game.request_draw_due_to_repeated_position());
+    }
+
+    #[test]
+    fn clones_keep_their_own_history() {
+        let mut game = ChessGame::default();
+
+        for chess_move in ["g1f3", "g8f6", "f3g1"] {
+            game.make_move(&chess_move.parse().unwrap()).unwrap();
+        }
+
+        let mut clone = game.clone();
+        clone.make_move(&"f6g8".parse().unwrap()).unwrap();
+        clone.undo_move().unwrap();
+        clone.undo_move().unwrap();
+
+        assert_eq!(game.get_moves().len(), 3);
+        assert_eq!(clone.get_moves().len(), 2);
+
+        game.undo_move().unwrap();
+
+        assert_eq!(game.to_fen(), clone.to_fen());
+    }
+
+    #[test]
+    fn undo_promotion() {
+        let mut game = ChessGame::from_fen(String::from("8/4P3/8/8/8/8/k7/4K3 w - - 0 1")).unwrap();
+
+        game.make_move(&"e7e8q".parse().unwrap()).unwrap();
+
+        assert_eq!(game.get_moves(), ["e7e8q".parse().unwrap()]);
+        assert_eq!(game.undo_move().unwrap(), "e7e8q".parse().unwrap());
+        assert_eq!(game.to_fen(), "8/4P3/8/8/8/8/k7/4K3 w - - 0 1");
+    }
+
+    #[test]
+    fn rejects_invalid_notation() {
+        for notation in ["", "e2", "e2e9", "e7e8x", "K@", "e2e4qq", "ä2e4"] {
+            assert!(notation.parse::<ChessMove>().is_err());
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use std::mem;
+
+use crate::board::GameState;
+use crate::chess_move::ChessMove;
+use crate::piece::{Color, Piece};
+use crate::position::{self, BoardPosition, File, File::*, Rank};
+use crate::{ChessError, ChessGame};
+
+impl ChessGame {
+    /// Parses a move in standard algebraic notation, such as `Nf3`, `exd5`, `e8=Q+`, `O-O` or
+    /// `N@f3`, in the current position.
+    ///
+    /// Fails if the move is not legal or if more than one move matches the notation.
+    pub fn parse_san(&self, san: &str) -> Result<ChessMove, ChessError> {
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+
+        if !san.is_ascii() || san.is_empty() {
+            return Err(ChessError::InvalidMove);
+        }
+
+        if let Some(to) = san.strip_prefix('@') {
+            return self.parse_san(&format!("P@{to}"));
+        }
+
+        if san.contains('@') {
+            let chess_move: ChessMove = san.parse()?;
+
+            return match &chess_move {
+                ChessMove::Drop { piece, to } if self.get_valid_drops(piece).get(to).is_some() => Ok(chess_move),
+                _ => Err(ChessError::InvalidMove),
+            };
+        }
+
+        if let Some(king_side) = match san {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        } {
+            let from = self
+                .get_king_position(self.get_player_turn())
+                .ok_or(ChessError::InvalidMove)?
+                .clone();
+            let file = if king_side { G } else { C };
+            let to = BoardPosition::from((file, from.get_rank().clone()));
+
+            if self.get_valid_moves(&from).get(&to).is_none() {
+                return Err(ChessError::InvalidMove);
+            }
+
+            return Ok(ChessMove::Move {
+                from,
+                to,
+                promotion: None,
+            });
+        }
+
+        let (piece, rest) = match san.chars().next() {
+            Some(letter @ ('N' | 'B' | 'R' | 'Q' | 'K')) => (Color::try_from(letter)?, &san[1..]),
+            _ => (Color::try_from('P')?, san),
+        };
+
+        let (rest, promotion) = match rest.split_once('=') {
+            Some((rest, promotion)) => (rest, Some(promotion)),
+            None => match rest.chars().last() {
+                Some('N' | 'B' | 'R' | 'Q' | 'K') => (&rest[..rest.len() - 1], Some(&rest[rest.len() - 1..])),
+                _ => (rest, None),
+            },
+        };
+
+        let promotion = match promotion.map(|letter| letter.chars().collect::<Vec<char>>()).as_deref() {
+            Some([letter @ ('N' | 'B' | 'R' | 'Q' | 'K')]) => Some(Color::try_from(*letter)?.get_internal().clone()),
+            Some(_) => return Err(ChessError::InvalidMove),
+            None => None,
+        };
+
+        let rest: String = rest.chars().filter(|char| !matches!(char, 'x' | ':' | '-')).collect();
+
+        if rest.len() < 2 {
+            return Err(ChessError::InvalidMove);
+        }
+
+        let to: BoardPosition = rest[rest.len() - 2..].parse()?;
+
+        // The file and rank given to tell apart moves of the same kind of piece
+        let mut file = None;
+        let mut rank = None;
+
+        for char in rest[..rest.len() - 2].chars() {
+            match char {
+                'a'..='h' => file = Some(File::try_from(char as u8 - b'a')?),
+                '1'..='8' => rank = Some(Rank::try_from(char as u8 - b'1')?),
+                _ => return Err(ChessError::InvalidMove),
+            }
+        }
+
+        let mut candidates = position::iter().into_iter().filter(|from| {
+            matches!(self.get_square(from), Some(square) if square.same_color(self.get_player_turn())
+                && mem::discriminant(square.get_internal()) == mem::discriminant(piece.get_internal()))
+                && file.as_ref().is_none_or(|file| file == from.get_file())
+                && rank.as_ref().is_none_or(|rank| rank == from.get_rank())
+                && self.get_valid_moves(from).get(&to).is_some()
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(from), None) => Ok(ChessMove::Move {
+                from,
+                to,
+                promotion,
+            }),
+            _ => Err(ChessError::InvalidMove),
+        }
+    }
+
+    /// Formats a legal move in standard algebraic notation, including `+` for check and `#` for
+    /// checkmate
+    pub fn to_san(&self, chess_move: &ChessMove) -> Result<String, ChessError> {
+        let mut game = self.clone_position();
+        let state = game.make_move(chess_move)?;
+
+        let mut san = match chess_move {
+            ChessMove::Drop { .. } => chess_move.to_string(),
+            ChessMove::Move {
+                from,
+                to,
+                promotion,
+            } => {
+                let piece = self.get_square(from).as_ref().ok_or(ChessError::NoPiece)?;
+                let file_distance = i8::try_from(u8::from(to.get_file())).unwrap_or_default()
+                    - i8::try_from(u8::from(from.get_file())).unwrap_or_default();
+
+                match piece.get_internal() {
+                    Piece::King { .. } if file_distance == 2 => String::from("O-O"),
+                    Piece::King { .. } if file_distance == -2 => String::from("O-O-O"),
+                    Piece::Pawn { .. } => {
+                        let mut san = if file_distance == 0 {
+                            to.to_string()
+                        } else {
+                            format!("{}x{to}", &from.to_string()[..1])
+                        };
+
+                        if let Some(promotion) = promotion {
+                            san.push('=');
+                            san.push(char::from(&Color::White(promotion.clone())));
+                        }
+
+                        san
+                    }
+                    _ => {
+                        let mut san = String::from(char::from(&Color::White(piece.get_internal().clone())));
+
+                        san.push_str(&self.disambiguate(piece, from, to));
+
+                        if self.get_square(to).is_some() {
+                            san.push('x');
+                        }
+
+                        san.push_str(&to.to_string());
+
+                        san
+                    }
+                }
+            }
+        };
+
+        if matches!(state, GameState::CheckMate) {
+            san.push('#');
+        } else if game.is_in_check(game.get_player_turn()) {
+            san.push('+');
+        }
+
+        Ok(san)
+    }
+
+    // Returns the file, rank or square needed to tell the move apart from moves of the same kind
+    // of piece to the same square
+    fn disambiguate(&self, piece: &Color<Piece>, from: &BoardPosition, to: &BoardPosition) -> String {
+        let others: Vec<BoardPosition> = position::iter()
+            .into_iter()
+            .filter(|other| {
+                other != from
+                    && matches!(self.get_square(other), Some(square) if square.same_color(self.get_player_turn())
+                        && mem::discriminant(square.get_internal()) == mem::discriminant(piece.get_internal()))
+                    && self.get_valid_moves(other).get(to).is_some()
+            })
+            .collect();
+
+        let square = from.to_string();
+
+        if others.is_empty() {
+            String::new()
+        } else if others.iter().all(|other| other.get_file() != from.get_file()) {
+            square[..1].to_string()
+        } else if others.iter().all(|other| other.get_rank() != from.get_rank()) {
+            square[1..].to_string()
+        } else {
+            square
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::variant::Crazyhouse;
+    use crate::ChessGame;
+
+    #[test]
+    fn san_round_trip() {
+        let mut game = ChessGame::default();
+
+        let moves = [
+            ("e4", "e2e4"),
+            ("d5", "d7d5"),
+            ("exd5", "e4d5"),
+            ("Qxd5", "d8d5"),
+            ("Nc3", "b1c3"),
+            ("Qa5", "d5a5"),
+            ("Nf3", "g1f3"),
+            ("Nf6", "g8f6"),
+            ("Bc4", "f1c4"),
+            ("Bg4", "c8g4"),
+            ("O-O", "e1g1"),
+            ("Nc6", "b8c6"),
+            ("Re1", "f1e1"),
+            ("O-O-O", "e8c8"),
+            ("Bxf7", "c4f7"),
+            ("Rxd2", "d8d2"),
+            ("Nxd2", "f3d2"),
+        ];
+
+        for (san, chess_move) in moves {
+            let chess_move = chess_move.parse().unwrap();
+
+            assert_eq!(game.parse_san(san).unwrap(), chess_move, "{san}");
+            assert_eq!(game.to_san(&chess_move).unwrap(), san);
+
+            game.make_move(&chess_move).unwrap();
+        }
+    }
+
+    #[test]
+    fn disambiguation_checks_and_promotion() {
+        let game = ChessGame::from_fen(String::from("k7/4P3/8/8/8/8/1R5R/4K3 w - - 0 1")).unwrap();
+
+        assert_eq!(game.to_san(&"b2d2".parse().unwrap()).unwrap(), "Rbd2");
+        assert_eq!(game.parse_san("Rhd2").unwrap(), "h2d2".parse().unwrap());
+        assert!(game.parse_san("Rd2").is_err());
+
+        assert_eq!(game.to_san(&"e7e8q".parse().unwrap()).unwrap(), "e8=Q+");
+        assert_eq!(game.parse_san("e8=N").unwrap(), "e7e8n".parse().unwrap());
+        assert_eq!(game.to_san(&"h2h8".parse().unwrap()).unwrap(), "Rh8+");
+
+        let game = ChessGame::from_fen(String::from("6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1")).unwrap();
+
+        assert_eq!(game.to_san(&"a1a8".parse().unwrap()).unwrap(), "Ra8#");
+        assert!(game.parse_san("Qd8").is_err());
+    }
+
+    #[test]
+    fn drops() {
+        let mut game = ChessGame::new(Crazyhouse);
+
+        for san in ["e4", "d5", "exd5", "Qxd5"] {
+            let chess_move = game.parse_san(san).unwrap();
+            game.make_move(&chess_move).unwrap();
+        }
+
+        let drop = game.parse_san("P@e6").unwrap();
+
+        assert_eq!(drop, "P@e6".parse().unwrap());
+        assert_eq!(game.parse_san("@e6").unwrap(), drop);
+        assert_eq!(game.to_san(&drop).unwrap(), "P@e6");
+    }
+}
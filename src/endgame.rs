@@ -0,0 +1,823 @@
+//! Exact solutions of basic endgames, generated in memory by retrograde analysis.
+//!
+//! The solver knows the endgames where one side has only its king and the other side a queen,
+//! a rook, a bishop and a knight or a single pawn. Each table is generated the first time a
+//! position of the endgame is probed, starting from every checkmate and working backwards. The
+//! distances ignore the fifty move rule.
+//!
+//! ```rust
+//! use viktoe_chess::endgame::EndgameSolver;
+//! use viktoe_chess::prelude::*;
+//! use viktoe_chess::search::Score;
+//!
+//! let solver = EndgameSolver::new();
+//! let game = ChessGame::from_fen(String::from("k7/8/1K6/8/8/8/8/7R w - - 0 1")).unwrap();
+//!
+//! let solution = solver.probe(&game).unwrap();
+//!
+//! assert_eq!(solution.score, Score::Mate(1));
+//! assert_eq!(solution.best_move, Some("h1h8".parse().unwrap()));
+//! ```
+
+use std::mem;
+use std::sync::OnceLock;
+
+use crate::board::Turn;
+use crate::chess_move::ChessMove;
+use crate::piece::{Color, Piece};
+use crate::search::Score;
+use crate::ChessGame;
+
+/// The endgames the solver knows, named by the pieces the stronger side has besides its king
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endgame {
+    Queen,
+    Rook,
+    BishopKnight,
+    Pawn,
+}
+
+impl Endgame {
+    fn get_pieces(self) -> &'static [Kind] {
+        match self {
+            Endgame::Queen => &[Kind::Queen],
+            Endgame::Rook => &[Kind::Rook],
+            Endgame::BishopKnight => &[Kind::Bishop, Kind::Knight],
+            Endgame::Pawn => &[Kind::Pawn],
+        }
+    }
+}
+
+/// The solution of a position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    /// The exact distance to mate for the player whos turn it is, or zero centipawns for a draw
+    pub score: Score,
+    /// A move keeping the result, the fastest mate when winning and the longest defence when
+    /// losing. None if the player has no moves.
+    pub best_move: Option<ChessMove>,
+}
+
+/// Solves basic endgames, keeping the generated tables for later probes
+#[derive(Debug, Default)]
+pub struct EndgameSolver {
+    tables: [OnceLock<Table>; 4],
+}
+
+impl EndgameSolver {
+    /// Creates a solver without any generated tables
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates the table of an endgame now instead of at the first probe
+    pub fn generate(&self, endgame: Endgame) {
+        self.get_table(endgame);
+    }
+
+    /// Returns the distance to mate and the best move of a position of one of the endgames, or
+    /// None for any other position and for illegal positions. Positions of variants and positions
+    /// with castling rights are not solved.
+    pub fn probe(&self, game: &ChessGame) -> Option<Solution> {
+        let value = match self.get_value(game)? {
+            (Some(_), value) => value,
+            // The trivial draws are only solved as the result of a capture
+            (None, _) => return None,
+        };
+
+        let mut best: Option<(Value, ChessMove)> = None;
+
+        for chess_move in game.get_legal_moves() {
+            let mut child = game.clone_position();
+            child.play_unchecked(&chess_move);
+
+            // The value for the player making the move
+            let child_value = match self.get_value(&child)?.1 {
+                Value::Win(plies) => Value::Loss(plies + 1),
+                Value::Loss(plies) => Value::Win(plies + 1),
+                Value::Draw => Value::Draw,
+            };
+
+            if best.as_ref().is_none_or(|(best, _)| child_value > *best) {
+                best = Some((child_value, chess_move));
+            }
+        }
+
+        Some(Solution {
+            score: match value {
+                Value::Win(plies) => Score::Mate((plies as i32 + 1) / 2),
+                Value::Loss(plies) => Score::Mate(-(plies as i32) / 2),
+                Value::Draw => Score::Centipawns(0),
+            },
+            best_move: best.map(|(_, chess_move)| chess_move),
+        })
+    }
+
+    fn get_table(&self, endgame: Endgame) -> &Table {
+        let index = endgame as usize;
+
+        self.tables[index].get_or_init(|| Table::generate(endgame.get_pieces(), self))
+    }
+
+    // Returns the endgame and the value of a position for the player whos turn it is, the
+    // endgame is None for the draws with too little material to mate
+    fn get_value(&self, game: &ChessGame) -> Option<(Option<Endgame>, Value)> {
+        if !game.get_variant().is_standard() {
+            return None;
+        }
+
+        let mut kings = [0, 0];
+        let mut pieces = [Vec::new(), Vec::new()];
+
+        for (index, piece) in game.board.iter().enumerate() {
+            let (side, piece) = match piece {
+                Some(Color::White(piece)) => (0, piece),
+                Some(Color::Black(piece)) => (1, piece),
+                None => continue,
+            };
+
+            // The tables number the squares from a1, the board starts at a8
+            let square = (index ^ 56) as u8;
+
+            let kind = match piece {
+                Piece::King {
+                    castling_state: (false, false),
+                    ..
+                } => {
+                    kings[side] = square;
+                    continue;
+                }
+                Piece::King { .. } => return None,
+                Piece::Queen => Kind::Queen,
+                Piece::Rook => Kind::Rook,
+                Piece::Bishop => Kind::Bishop,
+                Piece::Knight => Kind::Knight,
+                Piece::Pawn { .. } => Kind::Pawn,
+            };
+
+            pieces[side].push((kind, square));
+        }
+
+        let strong = if pieces[1].is_empty() { 0 } else { 1 };
+
+        if !pieces[1 - strong].is_empty() {
+            return None;
+        }
+
+        let mut strong_pieces = mem::take(&mut pieces[strong]);
+        strong_pieces.sort_by_key(|(kind, _)| *kind);
+
+        let kinds: Vec<Kind> = strong_pieces.iter().map(|(kind, _)| *kind).collect();
+
+        let endgame = match kinds.as_slice() {
+            [] | [Kind::Bishop] | [Kind::Knight] => return Some((None, Value::Draw)),
+            [Kind::Queen] => Endgame::Queen,
+            [Kind::Rook] => Endgame::Rook,
+            [Kind::Bishop, Kind::Knight] => Endgame::BishopKnight,
+            [Kind::Pawn] => Endgame::Pawn,
+            _ => return None,
+        };
+
+        // The tables have the stronger side playing up the board as white
+        let flip = if strong == 1 { 56 } else { 0 };
+
+        let mut squares = [0; 4];
+        squares[0] = kings[strong] ^ flip;
+        squares[1] = kings[1 - strong] ^ flip;
+
+        for (index, (_, square)) in strong_pieces.iter().enumerate() {
+            squares[index + 2] = square ^ flip;
+        }
+
+        let strong_to_move = matches!(
+            (game.get_player_turn(), strong),
+            (Turn::White, 0) | (Turn::Black, 1)
+        );
+
+        let table = self.get_table(endgame);
+
+        if !table.is_legal(&squares, strong_to_move) {
+            return None;
+        }
+
+        let index = table.get_index(&squares, strong_to_move);
+
+        let value = match table.values[index] {
+            0 => Value::Draw,
+            value if strong_to_move => Value::Win(u32::from(value) - 1),
+            value => Value::Loss(u32::from(value) - 1),
+        };
+
+        Some((Some(endgame), value))
+    }
+}
+
+// The value of a position for the player whos turn it is, with the distance to mate in plies.
+// Faster wins and slower losses are greater.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Value {
+    Loss(u32),
+    Draw,
+    Win(u32),
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let rank = |value: &Value| match value {
+            Value::Loss(plies) => (0, i64::from(*plies)),
+            Value::Draw => (1, 0),
+            Value::Win(plies) => (2, -i64::from(*plies)),
+        };
+
+        rank(self).cmp(&rank(other))
+    }
+}
+
+// The pieces of the stronger side, ordered as the pieces of an endgame are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Queen,
+    Rook,
+    Bishop,
+    Knight,
+    Pawn,
+}
+
+const KING_STEPS: [(i8, i8); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+const KNIGHT_STEPS: [(i8, i8); 8] = [(-2, -1), (-2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2), (2, -1), (2, 1)];
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+// The squares of the a1-d1-d4 triangle, every position without pawns can be turned so the
+// stronger king stands on one of them
+const TRIANGLE: [u8; 10] = [0, 1, 2, 3, 9, 10, 11, 18, 19, 27];
+
+// The counter of a position where the weaker king can capture a piece, it is never lost
+const ESCAPES: u8 = u8::MAX;
+
+fn step(square: u8, (file, rank): (i8, i8)) -> Option<u8> {
+    let file = (square & 7) as i8 + file;
+    let rank = (square >> 3) as i8 + rank;
+
+    ((0..8).contains(&file) && (0..8).contains(&rank)).then_some((file + rank * 8) as u8)
+}
+
+fn is_adjacent(first: u8, second: u8) -> bool {
+    (first & 7).abs_diff(second & 7) <= 1 && (first >> 3).abs_diff(second >> 3) <= 1
+}
+
+// The positions of one endgame, the squares are the stronger king, the weaker king and then the
+// pieces of the stronger side
+struct Table {
+    pieces: Vec<Kind>,
+    has_pawns: bool,
+    // The distance to mate in plies plus one, 0 for a draw or an illegal position
+    values: Vec<u8>,
+}
+
+impl std::fmt::Debug for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Table").field("pieces", &self.pieces).finish()
+    }
+}
+
+impl Table {
+    fn generate(pieces: &[Kind], solver: &EndgameSolver) -> Self {
+        let has_pawns = pieces.contains(&Kind::Pawn);
+        let kings = if has_pawns { 32 } else { TRIANGLE.len() };
+
+        let mut table = Self {
+            pieces: pieces.to_vec(),
+            has_pawns,
+            values: vec![0; kings * 64usize.pow(pieces.len() as u32 + 1) * 2],
+        };
+
+        table.solve(solver);
+
+        table
+    }
+
+    fn count(&self) -> usize {
+        self.pieces.len() + 2
+    }
+
+    // Returns the index of the position, the same for every position it can be mirrored or
+    // turned into
+    fn get_index(&self, squares: &[u8; 4], strong_to_move: bool) -> usize {
+        // Mirror the board so the stronger king is on the a to d files, and without pawns on the
+        // first four ranks
+        let mut mirror = if squares[0] & 7 > 3 { 7 } else { 0 };
+
+        if !self.has_pawns && squares[0] >> 3 > 3 {
+            mirror |= 56;
+        }
+
+        let index = |transpose: bool| {
+            let transform = |square: u8| {
+                let square = square ^ mirror;
+
+                if transpose {
+                    (square >> 3) | (square & 7) << 3
+                } else {
+                    square
+                }
+            };
+
+            let king = transform(squares[0]);
+            let (file, rank) = (usize::from(king & 7), usize::from(king >> 3));
+
+            let mut index = if self.has_pawns {
+                rank * 4 + file
+            } else {
+                [0, 4, 7, 9][rank] + file - rank
+            };
+
+            for square in &squares[1..self.count()] {
+                index = index * 64 + usize::from(transform(*square));
+            }
+
+            index * 2 + usize::from(strong_to_move)
+        };
+
+        // Without pawns the board is also turned along the a1-h8 diagonal so the king is below
+        // it, a king on the diagonal can be turned either way
+        let king = squares[0] ^ mirror;
+
+        if self.has_pawns || king >> 3 < king & 7 {
+            index(false)
+        } else if king >> 3 > king & 7 {
+            index(true)
+        } else {
+            index(false).min(index(true))
+        }
+    }
+
+    fn decode(&self, mut index: usize) -> ([u8; 4], bool) {
+        let strong_to_move = index % 2 == 1;
+        index /= 2;
+
+        let mut squares = [0; 4];
+
+        for square in squares[1..self.count()].iter_mut().rev() {
+            *square = (index % 64) as u8;
+            index /= 64;
+        }
+
+        squares[0] = if self.has_pawns {
+            (index / 4 * 8 + index % 4) as u8
+        } else {
+            TRIANGLE[index]
+        };
+
+        (squares, strong_to_move)
+    }
+
+    fn is_legal(&self, squares: &[u8; 4], strong_to_move: bool) -> bool {
+        let squares = &squares[..self.count()];
+
+        for (index, square) in squares.iter().enumerate() {
+            if squares[..index].contains(square) {
+                return false;
+            }
+        }
+
+        let pawn_on_last_rank = self
+            .pieces
+            .iter()
+            .zip(&squares[2..])
+            .any(|(kind, square)| *kind == Kind::Pawn && !(8..56).contains(square));
+
+        if pawn_on_last_rank || is_adjacent(squares[0], squares[1]) {
+            return false;
+        }
+
+        // The weaker king can not be in check with the stronger side to move
+        !strong_to_move || !self.is_attacked(squares, squares[1], None, false)
+    }
+
+    // Returns whether the stronger side attacks a square, without the captured piece and
+    // optionally without the weaker king blocking the lines
+    fn is_attacked(&self, squares: &[u8], target: u8, captured: Option<usize>, without_king: bool) -> bool {
+        if is_adjacent(squares[0], target) {
+            return true;
+        }
+
+        let mut occupied = 0u64;
+
+        for (index, square) in squares[..self.count()].iter().enumerate() {
+            if Some(index) != captured && !(without_king && index == 1) {
+                occupied |= 1 << square;
+            }
+        }
+
+        self.pieces.iter().zip(&squares[2..]).enumerate().any(|(index, (kind, from))| {
+            if Some(index + 2) == captured {
+                return false;
+            }
+
+            let file = (target & 7) as i8 - (from & 7) as i8;
+            let rank = (target >> 3) as i8 - (from >> 3) as i8;
+
+            let straight = (file == 0) != (rank == 0);
+            let diagonal = file != 0 && file.abs() == rank.abs();
+
+            // The squares between a sliding piece and the target have to be empty
+            let is_clear = || {
+                let mut square = *from;
+
+                loop {
+                    square = (square as i8 + file.signum() + rank.signum() * 8) as u8;
+
+                    if square == target {
+                        return true;
+                    }
+
+                    if occupied & 1 << square != 0 {
+                        return false;
+                    }
+                }
+            };
+
+            match kind {
+                Kind::Queen => (straight || diagonal) && is_clear(),
+                Kind::Rook => straight && is_clear(),
+                Kind::Bishop => diagonal && is_clear(),
+                Kind::Knight => matches!((file.abs(), rank.abs()), (1, 2) | (2, 1)),
+                Kind::Pawn => rank == 1 && file.abs() == 1,
+            }
+        })
+    }
+
+    // Returns the squares the weaker king can move to and the piece it captures there
+    fn get_king_moves(&self, squares: &[u8; 4]) -> Vec<(u8, Option<usize>)> {
+        let squares = &squares[..self.count()];
+
+        KING_STEPS
+            .iter()
+            .filter_map(|offset| step(squares[1], *offset))
+            .filter_map(|to| {
+                let captured = squares.iter().position(|square| *square == to);
+
+                (captured != Some(0) && !self.is_attacked(squares, to, captured, true)).then_some((to, captured))
+            })
+            .collect()
+    }
+
+    // Returns the positions with the stronger side to move that lead to the position
+    fn get_strong_predecessors(&self, squares: &[u8; 4]) -> Vec<[u8; 4]> {
+        let count = self.count();
+        let is_empty = |square: u8| !squares[..count].contains(&square);
+        let mut predecessors = Vec::new();
+
+        let mut add = |index: usize, from: u8| {
+            let mut predecessor = *squares;
+            predecessor[index] = from;
+            predecessors.push(predecessor);
+        };
+
+        for offset in KING_STEPS {
+            if let Some(from) = step(squares[0], offset).filter(|from| is_empty(*from)) {
+                add(0, from);
+            }
+        }
+
+        for (index, kind) in self.pieces.iter().enumerate() {
+            let square = squares[index + 2];
+
+            let (directions, slides): (&[(i8, i8)], bool) = match kind {
+                Kind::Queen => (&KING_STEPS, true),
+                Kind::Rook => (&ROOK_DIRECTIONS, true),
+                Kind::Bishop => (&BISHOP_DIRECTIONS, true),
+                Kind::Knight => (&KNIGHT_STEPS, false),
+                Kind::Pawn => {
+                    // A pawn comes from the square behind it, or two squares behind on its
+                    // fourth rank
+                    if let Some(from) = step(square, (0, -1)).filter(|from| is_empty(*from) && *from >= 8) {
+                        add(index + 2, from);
+
+                        if square >> 3 == 3 && is_empty(from - 8) {
+                            add(index + 2, from - 8);
+                        }
+                    }
+
+                    continue;
+                }
+            };
+
+            for direction in directions {
+                let mut from = square;
+
+                while let Some(next) = step(from, *direction).filter(|next| is_empty(*next)) {
+                    add(index + 2, next);
+
+                    if !slides {
+                        break;
+                    }
+
+                    from = next;
+                }
+            }
+        }
+
+        predecessors
+    }
+
+    // The value of the best promotion for the stronger side to move, in plies plus one
+    fn get_promotion_value(&self, squares: &[u8; 4], solver: &EndgameSolver) -> Option<u8> {
+        let (index, square) = self
+            .pieces
+            .iter()
+            .zip(&squares[2..])
+            .enumerate()
+            .find(|(_, (kind, square))| **kind == Kind::Pawn && **square >= 48)
+            .map(|(index, (_, square))| (index, *square))?;
+
+        if squares[..self.count()].contains(&(square + 8)) {
+            return None;
+        }
+
+        let mut promoted = *squares;
+        promoted[index + 2] = square + 8;
+
+        // Promotions to a bishop or a knight leave too little material to mate
+        [Endgame::Queen, Endgame::Rook]
+            .into_iter()
+            .filter_map(|endgame| {
+                let table = solver.get_table(endgame);
+
+                match table.values[table.get_index(&promoted, false)] {
+                    0 => None,
+                    value => Some(value + 1),
+                }
+            })
+            .min()
+    }
+
+    // Finds the distance to mate of every position, one ply at a time starting from the
+    // checkmates. A position with the weaker side to move is lost once every position its king
+    // can move to is won for the stronger side.
+    fn solve(&mut self, solver: &EndgameSolver) {
+        // The positions found at each ply, and the promotions winning at each ply
+        let mut plies: Vec<Vec<u32>> = vec![Vec::new()];
+        let mut promotions: Vec<Vec<u32>> = Vec::new();
+
+        // The number of positions the weaker king can move to that are not known to be lost
+        let mut counters = vec![0u8; self.values.len()];
+
+        for (index, counter) in counters.iter_mut().enumerate() {
+            let (squares, strong_to_move) = self.decode(index);
+
+            if self.get_index(&squares, strong_to_move) != index || !self.is_legal(&squares, strong_to_move) {
+                continue;
+            }
+
+            if !strong_to_move {
+                let moves = self.get_king_moves(&squares);
+
+                if moves.iter().any(|(_, captured)| captured.is_some()) {
+                    // Capturing leaves too little material to mate
+                    *counter = ESCAPES;
+                } else if !moves.is_empty() {
+                    let mut successors: Vec<usize> = moves
+                        .iter()
+                        .map(|(to, _)| {
+                            let mut successor = squares;
+                            successor[1] = *to;
+
+                            self.get_index(&successor, true)
+                        })
+                        .collect();
+
+                    successors.sort_unstable();
+                    successors.dedup();
+
+                    *counter = successors.len() as u8;
+                } else if self.is_attacked(&squares, squares[1], None, false) {
+                    self.values[index] = 1;
+                    plies[0].push(index as u32);
+                }
+            } else if self.has_pawns {
+                if let Some(value) = self.get_promotion_value(&squares, solver) {
+                    let ply = usize::from(value) - 1;
+
+                    if promotions.len() <= ply {
+                        promotions.resize(ply + 1, Vec::new());
+                    }
+
+                    promotions[ply].push(index as u32);
+                }
+            }
+        }
+
+        let mut ply = 0;
+
+        while ply < plies.len() || ply < promotions.len() {
+            let mut positions = plies.get_mut(ply).map(mem::take).unwrap_or_default();
+
+            for index in promotions.get_mut(ply).map(mem::take).unwrap_or_default() {
+                if self.values[index as usize] == 0 {
+                    self.values[index as usize] = ply as u8 + 1;
+                    positions.push(index);
+                }
+            }
+
+            let mut found = Vec::new();
+
+            for index in positions {
+                let (squares, strong_to_move) = self.decode(index as usize);
+
+                if strong_to_move {
+                    // The positions the weaker king moved from, each counted once even if the
+                    // king can reach the won position in more than one way
+                    let mut predecessors: Vec<usize> = KING_STEPS
+                        .iter()
+                        .filter_map(|offset| step(squares[1], *offset))
+                        .filter(|from| !squares[..self.count()].contains(from))
+                        .filter_map(|from| {
+                            let mut predecessor = squares;
+                            predecessor[1] = from;
+
+                            self.is_legal(&predecessor, false)
+                                .then(|| self.get_index(&predecessor, false))
+                        })
+                        .collect();
+
+                    predecessors.sort_unstable();
+                    predecessors.dedup();
+
+                    for predecessor in predecessors {
+                        if self.values[predecessor] != 0 || counters[predecessor] == ESCAPES {
+                            continue;
+                        }
+
+                        counters[predecessor] -= 1;
+
+                        if counters[predecessor] == 0 {
+                            self.values[predecessor] = ply as u8 + 2;
+                            found.push(predecessor as u32);
+                        }
+                    }
+                } else {
+                    for predecessor in self.get_strong_predecessors(&squares) {
+                        if !self.is_legal(&predecessor, true) {
+                            continue;
+                        }
+
+                        let predecessor_index = self.get_index(&predecessor, true);
+
+                        if self.values[predecessor_index] == 0 {
+                            self.values[predecessor_index] = ply as u8 + 2;
+                            found.push(predecessor_index as u32);
+                        }
+                    }
+                }
+            }
+
+            if !found.is_empty() {
+                if plies.len() <= ply + 1 {
+                    plies.resize(ply + 2, Vec::new());
+                }
+
+                plies[ply + 1].extend(found);
+            }
+
+            ply += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::KingOfTheHill;
+
+    fn solve(solver: &EndgameSolver, fen: &str) -> Solution {
+        solver.probe(&ChessGame::from_fen(String::from(fen)).unwrap()).unwrap()
+    }
+
+    // Plays the best moves of both sides, returning the number of plies until the game ends
+    fn play_out(solver: &EndgameSolver, fen: &str) -> usize {
+        let mut game = ChessGame::from_fen(String::from(fen)).unwrap();
+        let mut plies = 0;
+
+        while let Some(Solution {
+            best_move: Some(chess_move),
+            ..
+        }) = solver.probe(&game)
+        {
+            game.make_move(&chess_move).unwrap();
+            plies += 1;
+        }
+
+        plies
+    }
+
+    #[test]
+    fn solves_queen_and_rook_endgames() {
+        let solver = EndgameSolver::new();
+
+        assert_eq!(solve(&solver, "k7/8/1K6/8/8/8/8/6Q1 w - - 0 1").score, Score::Mate(1));
+        assert_eq!(solve(&solver, "k5Q1/8/1K6/8/8/8/8/8 b - - 0 1").score, Score::Mate(0));
+
+        // The longest mates with a queen and with a rook take 10 and 16 moves
+        let longest = |endgame| solver.get_table(endgame).values.iter().skip(1).step_by(2).max().copied();
+
+        assert_eq!(longest(Endgame::Queen), Some(20));
+        assert_eq!(longest(Endgame::Rook), Some(32));
+
+        let solution = solve(&solver, "8/8/3k4/8/8/8/8/KR6 w - - 0 1");
+
+        // The same position with the colors swapped
+        assert_eq!(solve(&solver, "kr6/8/8/8/8/3K4/8/8 b - - 0 1").score, solution.score);
+
+        let Score::Mate(moves) = solution.score else {
+            panic!("KRK is a win");
+        };
+
+        assert_eq!(play_out(&solver, "8/8/3k4/8/8/8/8/KR6 w - - 0 1"), moves as usize * 2 - 1);
+
+        // The king takes the undefended queen
+        let solution = solve(&solver, "8/8/8/8/8/8/1Q6/k5K1 b - - 0 1");
+
+        assert_eq!(solution.score, Score::Centipawns(0));
+        assert_eq!(solution.best_move, Some("a1b2".parse().unwrap()));
+
+        // Stalemate
+        assert_eq!(solve(&solver, "k7/2Q5/1K6/8/8/8/8/8 b - - 0 1").score, Score::Centipawns(0));
+    }
+
+    #[test]
+    fn solves_bishop_and_knight_endgames() {
+        let solver = EndgameSolver::new();
+
+        // The longest mate with a bishop and a knight takes 33 moves
+        let longest = solver.get_table(Endgame::BishopKnight).values.iter().skip(1).step_by(2).max().copied();
+
+        assert_eq!(longest, Some(66));
+
+        let solution = solve(&solver, "8/8/8/4k3/8/8/8/KBN5 w - - 0 1");
+
+        // The same position with the colors swapped
+        assert_eq!(solve(&solver, "kbn5/8/8/8/4K3/8/8/8 b - - 0 1").score, solution.score);
+
+        let Score::Mate(moves) = solution.score else {
+            panic!("KBNK is a win");
+        };
+
+        assert_eq!(play_out(&solver, "8/8/8/4k3/8/8/8/KBN5 w - - 0 1"), moves as usize * 2 - 1);
+
+        // The king takes the undefended knight
+        assert_eq!(solve(&solver, "8/8/8/8/8/8/8/kNB1K3 b - - 0 1").score, Score::Centipawns(0));
+    }
+
+    #[test]
+    fn solves_pawn_endgames() {
+        let solver = EndgameSolver::new();
+
+        // The king in front of its pawn on the sixth rank always wins
+        assert!(matches!(solve(&solver, "4k3/8/4K3/4P3/8/8/8/8 w - - 0 1").score, Score::Mate(moves) if moves > 0));
+
+        // Further back the opposition decides
+        assert_eq!(solve(&solver, "8/4k3/8/4K3/4P3/8/8/8 w - - 0 1").score, Score::Centipawns(0));
+        assert!(matches!(solve(&solver, "8/4k3/8/4K3/4P3/8/8/8 b - - 0 1").score, Score::Mate(moves) if moves < 0));
+
+        // A rook pawn is a draw when the king reaches the corner
+        assert_eq!(solve(&solver, "k7/8/8/8/8/8/P7/K7 w - - 0 1").score, Score::Centipawns(0));
+
+        // The pawn promotes with mate
+        assert_eq!(solve(&solver, "k7/2P5/1K6/8/8/8/8/8 w - - 0 1").score, Score::Mate(1));
+
+        // Black with the pawn
+        assert!(matches!(solve(&solver, "8/8/8/8/4p3/4k3/8/4K3 b - - 0 1").score, Score::Mate(moves) if moves > 0));
+    }
+
+    #[test]
+    fn unknown_positions() {
+        let solver = EndgameSolver::new();
+
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "4k3/8/8/8/8/8/8/R3K3 w Q - 0 1",
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/QQ2K3 w - - 0 1",
+            "4k3/4r3/8/8/8/8/8/Q3K3 w - - 0 1",
+        ] {
+            assert_eq!(solver.probe(&ChessGame::from_fen(String::from(fen)).unwrap()), None, "{fen}");
+        }
+
+        // The king of the side not to move is in check
+        assert_eq!(solver.probe(&ChessGame::from_fen(String::from("1k6/8/8/8/8/8/8/KQ6 w - - 0 1")).unwrap()), None);
+
+        // Only the rules of standard chess are solved
+        assert_eq!(
+            solver.probe(&ChessGame::from_fen_with_variant(String::from("k7/8/1K6/8/8/8/8/6Q1 w - - 0 1"), KingOfTheHill).unwrap()),
+            None
+        );
+    }
+}
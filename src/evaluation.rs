@@ -0,0 +1,402 @@
+mod tables;
+
+use crate::board::check::is_in_check;
+use crate::board::Turn;
+use crate::piece::{Color, Piece};
+use crate::position::{self, BoardPosition};
+use crate::ChessGame;
+use tables::*;
+
+/// A weight in the middlegame and in the endgame, interpolated by the phase of the game
+pub type Tapered = (i32, i32);
+
+/// The phase of a game where every piece except the pawns and kings is still on the board
+pub const MAX_PHASE: i32 = 24;
+
+/// The weights of every term of the evaluation in centipawns, can be changed to tune the
+/// evaluation.
+///
+/// ```rust
+/// use viktoe_chess::prelude::*;
+/// use viktoe_chess::evaluation::Weights;
+///
+/// let weights = Weights {
+///     mobility: (0, 0),
+///     ..Weights::default()
+/// };
+///
+/// let evaluation = weights.evaluate(&ChessGame::default());
+///
+/// assert_eq!(evaluation.mobility, 0);
+/// assert_eq!(evaluation.total(), 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Weights {
+    pub pawn: Tapered,
+    pub knight: Tapered,
+    pub bishop: Tapered,
+    pub rook: Tapered,
+    pub queen: Tapered,
+    /// Scale of the piece-square tables in percent
+    pub piece_square_scale: i32,
+    /// Penalty for each pawn on a file after the first
+    pub doubled_pawn: Tapered,
+    /// Penalty for a pawn without pawns of the same color on the files next to it
+    pub isolated_pawn: Tapered,
+    /// Bonus for a pawn without opposing pawns in front of it, indexed by the rank counted from
+    /// the side of the player
+    pub passed_pawn: [Tapered; 8],
+    /// Bonus for each pawn in the two ranks in front of the king
+    pub pawn_shield: Tapered,
+    /// Penalty for each square next to the king attacked by the opponent
+    pub king_zone_attack: Tapered,
+    /// Bonus for each square a knight, bishop, rook or queen can move to
+    pub mobility: Tapered,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            pawn: (82, 94),
+            knight: (337, 281),
+            bishop: (365, 297),
+            rook: (477, 512),
+            queen: (1025, 936),
+            piece_square_scale: 100,
+            doubled_pawn: (-10, -20),
+            isolated_pawn: (-10, -15),
+            passed_pawn: [
+                (0, 0),
+                (5, 10),
+                (5, 15),
+                (10, 25),
+                (20, 45),
+                (35, 80),
+                (60, 120),
+                (0, 0),
+            ],
+            pawn_shield: (10, 0),
+            king_zone_attack: (-15, -5),
+            mobility: (4, 4),
+        }
+    }
+}
+
+/// The evaluation of a position in centipawns from the view of white, with each term reported
+/// separately.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Evaluation {
+    pub material: i32,
+    pub piece_square: i32,
+    pub pawn_structure: i32,
+    pub king_safety: i32,
+    pub mobility: i32,
+    /// The phase the terms were interpolated with, from [`MAX_PHASE`] at the start of the game
+    /// to 0 when only pawns and kings are left
+    pub phase: i32,
+}
+
+impl Evaluation {
+    /// Returns the sum of every term from the view of white
+    pub fn total(&self) -> i32 {
+        self.material + self.piece_square + self.pawn_structure + self.king_safety + self.mobility
+    }
+
+    /// Returns the sum of every term from the view of `player_color`
+    pub fn relative(&self, player_color: &Turn) -> i32 {
+        match player_color {
+            Turn::White => self.total(),
+            Turn::Black => -self.total(),
+        }
+    }
+}
+
+impl Weights {
+    /// Evaluates the position of a game
+    pub fn evaluate(&self, game: &ChessGame) -> Evaluation {
+        let pieces: Vec<(BoardPosition, &Color<Piece>)> = position::iter()
+            .into_iter()
+            .zip(game.iter())
+            .filter_map(|(position, piece)| piece.as_ref().map(|piece| (position, piece)))
+            .collect();
+
+        let phase = pieces
+            .iter()
+            .map(|(_, piece)| match piece.get_internal() {
+                Piece::Knight | Piece::Bishop => 1,
+                Piece::Rook => 2,
+                Piece::Queen => 4,
+                _ => 0,
+            })
+            .sum::<i32>()
+            .min(MAX_PHASE);
+
+        let taper = |(middlegame, endgame): Tapered| {
+            (middlegame * phase + endgame * (MAX_PHASE - phase)) / MAX_PHASE
+        };
+
+        let mut material = (0, 0);
+        let mut piece_square = (0, 0);
+        let mut mobility = (0, 0);
+
+        for (position, piece) in &pieces {
+            let sign = if piece.same_color(&Turn::White) { 1 } else { -1 };
+
+            let value = match piece.get_internal() {
+                Piece::Pawn { .. } => self.pawn,
+                Piece::Knight => self.knight,
+                Piece::Bishop => self.bishop,
+                Piece::Rook => self.rook,
+                Piece::Queen => self.queen,
+                Piece::King { .. } => (0, 0),
+            };
+            add(&mut material, value, sign);
+
+            let index = table_index(position, piece);
+            let (middlegame, endgame) = match piece.get_internal() {
+                Piece::Pawn { .. } => (PAWN_MIDDLEGAME[index], PAWN_ENDGAME[index]),
+                Piece::Knight => (KNIGHT[index], KNIGHT[index]),
+                Piece::Bishop => (BISHOP[index], BISHOP[index]),
+                Piece::Rook => (ROOK[index], ROOK[index]),
+                Piece::Queen => (QUEEN[index], QUEEN[index]),
+                Piece::King { .. } => (KING_MIDDLEGAME[index], KING_ENDGAME[index]),
+            };
+            add(
+                &mut piece_square,
+                (
+                    middlegame * self.piece_square_scale / 100,
+                    endgame * self.piece_square_scale / 100,
+                ),
+                sign,
+            );
+
+            if matches!(
+                piece.get_internal(),
+                Piece::Knight | Piece::Bishop | Piece::Rook | Piece::Queen
+            ) {
+                let color = if sign == 1 { Turn::White } else { Turn::Black };
+                let squares = piece
+                    .get_standard_moves(&game.board, position, &color)
+                    .iter()
+                    .filter(|square| square.is_some())
+                    .count() as i32;
+
+                add(&mut mobility, (self.mobility.0 * squares, self.mobility.1 * squares), sign);
+            }
+        }
+
+        let white_pawn_structure = self.pawn_structure(&pieces, &Turn::White);
+        let black_pawn_structure = self.pawn_structure(&pieces, &Turn::Black);
+        let pawn_structure = (
+            white_pawn_structure.0 - black_pawn_structure.0,
+            white_pawn_structure.1 - black_pawn_structure.1,
+        );
+
+        let white_king_safety = self.king_safety(game, &pieces, &Turn::White);
+        let black_king_safety = self.king_safety(game, &pieces, &Turn::Black);
+        let king_safety = (
+            white_king_safety.0 - black_king_safety.0,
+            white_king_safety.1 - black_king_safety.1,
+        );
+
+        Evaluation {
+            material: taper(material),
+            piece_square: taper(piece_square),
+            pawn_structure: taper(pawn_structure),
+            king_safety: taper(king_safety),
+            mobility: taper(mobility),
+            phase,
+        }
+    }
+
+    // Doubled, isolated and passed pawns of one player
+    fn pawn_structure(&self, pieces: &[(BoardPosition, &Color<Piece>)], player_color: &Turn) -> Tapered {
+        let pawns = |color: &Turn| -> Vec<(i8, i8)> {
+            pieces
+                .iter()
+                .filter(|(_, piece)| {
+                    piece.same_color(color) && matches!(piece.get_internal(), Piece::Pawn { .. })
+                })
+                .map(|(position, _)| {
+                    let (file, rank): (u8, u8) = position.into();
+                    (file as i8, rank as i8)
+                })
+                .collect()
+        };
+
+        let own_pawns = pawns(player_color);
+        let opposing_pawns = pawns(&player_color.opponent());
+
+        let mut score = (0, 0);
+
+        for file in 0..8 {
+            let count = own_pawns.iter().filter(|(pawn_file, _)| *pawn_file == file).count() as i32;
+
+            if count > 1 {
+                add(&mut score, self.doubled_pawn, count - 1);
+            }
+        }
+
+        for (file, rank) in &own_pawns {
+            let is_isolated = !own_pawns
+                .iter()
+                .any(|(other_file, _)| (other_file - file).abs() == 1);
+
+            if is_isolated {
+                add(&mut score, self.isolated_pawn, 1);
+            }
+
+            let is_in_front = |other_rank: i8| match player_color {
+                Turn::White => other_rank > *rank,
+                Turn::Black => other_rank < *rank,
+            };
+            let is_passed = !opposing_pawns.iter().any(|(other_file, other_rank)| {
+                (other_file - file).abs() <= 1 && is_in_front(*other_rank)
+            });
+
+            if is_passed {
+                let relative_rank = match player_color {
+                    Turn::White => *rank,
+                    Turn::Black => 7 - *rank,
+                };
+
+                add(&mut score, self.passed_pawn[relative_rank as usize], 1);
+            }
+        }
+
+        score
+    }
+
+    // Pawns sheltering the king and squares next to it attacked by the opponent
+    fn king_safety(
+        &self,
+        game: &ChessGame,
+        pieces: &[(BoardPosition, &Color<Piece>)],
+        player_color: &Turn,
+    ) -> Tapered {
+        let king_position = match game.get_king_position(player_color) {
+            Some(king_position) => king_position,
+            None => return (0, 0),
+        };
+
+        let direction = match player_color {
+            Turn::White => 1,
+            Turn::Black => -1,
+        };
+
+        let mut score = (0, 0);
+
+        let shield = pieces
+            .iter()
+            .filter(|(position, piece)| {
+                piece.same_color(player_color)
+                    && matches!(piece.get_internal(), Piece::Pawn { .. })
+                    && (-1..=1).any(|file| {
+                        (1..=2).any(|rank| {
+                            king_position.add((file, rank * direction)).ok().as_ref() == Some(position)
+                        })
+                    })
+            })
+            .count() as i32;
+
+        add(&mut score, self.pawn_shield, shield);
+
+        let attacked = (-1..=1)
+            .flat_map(|file| (-1..=1).map(move |rank| (file, rank)))
+            .filter(|vector| *vector != (0, 0))
+            .filter_map(|vector| king_position.add(vector).ok())
+            .filter(|position| is_in_check(&game.board, position, player_color))
+            .count() as i32;
+
+        add(&mut score, self.king_zone_attack, attacked);
+
+        score
+    }
+}
+
+impl ChessGame {
+    /// Evaluates the position with the default weights, see [`Weights`] for tuning the
+    /// evaluation.
+    pub fn evaluate(&self) -> Evaluation {
+        Weights::default().evaluate(self)
+    }
+}
+
+fn add(score: &mut Tapered, weight: Tapered, times: i32) {
+    score.0 += weight.0 * times;
+    score.1 += weight.1 * times;
+}
+
+// The index into the piece-square tables, which are written from the view of white
+fn table_index(position: &BoardPosition, piece: &Color<Piece>) -> usize {
+    let (file, rank): (u8, u8) = position.into();
+
+    match piece {
+        Color::White(_) => usize::from(file) + (7 - usize::from(rank)) * 8,
+        Color::Black(_) => usize::from(file) + usize::from(rank) * 8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(fen: &str) -> Evaluation {
+        ChessGame::from_fen(String::from(fen)).unwrap().evaluate()
+    }
+
+    #[test]
+    fn start_position_is_equal() {
+        let evaluation = ChessGame::default().evaluate();
+
+        assert_eq!(evaluation.total(), 0);
+        assert_eq!(evaluation.phase, MAX_PHASE);
+    }
+
+    #[test]
+    fn mirrored_positions_are_opposite() {
+        let white = evaluate("r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4");
+        let black = evaluate("rnbqk2r/pppp1ppp/5n2/2b1p3/4P3/2N2N2/PPPP1PPP/R1BQKB1R b KQkq - 4 4");
+
+        assert_eq!(white.total(), -black.total());
+        assert_eq!(white.pawn_structure, -black.pawn_structure);
+        assert_eq!(white.king_safety, -black.king_safety);
+    }
+
+    #[test]
+    fn material_is_counted() {
+        let evaluation = evaluate("4k3/8/8/8/8/8/8/3QK3 w - - 0 1");
+
+        assert_eq!(evaluation.phase, 4);
+        assert_eq!(evaluation.material, (1025 * 4 + 936 * 20) / 24);
+        assert!(evaluation.total() > 800);
+        assert_eq!(evaluation.relative(&Turn::Black), -evaluation.total());
+    }
+
+    #[test]
+    fn pawn_structure_terms() {
+        let weights = Weights::default();
+
+        // Doubled isolated pawns on the e-file against a single pawn on the h-file, both passed
+        let game = ChessGame::from_fen(String::from("4k3/8/8/8/4P3/4P3/7p/4K3 w - - 0 1")).unwrap();
+        let evaluation = weights.evaluate(&game);
+
+        let white = weights.doubled_pawn.1 + 2 * weights.isolated_pawn.1 + weights.passed_pawn[2].1 + weights.passed_pawn[3].1;
+        let black = weights.isolated_pawn.1 + weights.passed_pawn[6].1;
+
+        assert_eq!(evaluation.pawn_structure, white - black);
+    }
+
+    #[test]
+    fn exposed_king_is_penalized() {
+        let sheltered = evaluate("q5k1/5ppp/8/8/8/8/5PPP/Q5K1 w - - 0 1");
+        let exposed = evaluate("q5k1/5ppp/8/8/5PPP/8/8/Q5K1 w - - 0 1");
+
+        assert_eq!(sheltered.king_safety, 0);
+        assert!(exposed.king_safety < 0);
+
+        let attacked = evaluate("6k1/5ppp/8/8/8/8/5PPP/q5K1 w - - 0 1");
+
+        assert!(attacked.king_safety < 0);
+    }
+}
@@ -1,16 +1,29 @@
 pub mod prelude;
 mod test;
+pub mod analysis;
 pub mod board;
+pub mod book;
+pub mod chess_move;
+pub mod endgame;
+pub mod evaluation;
+pub mod pgn;
 pub mod piece;
 pub mod position;
+pub mod search;
+pub mod tablebase;
+pub mod variant;
 
 use std::collections::HashMap;
+use std::mem;
+use std::sync::Arc;
 
 use crate::position::{BoardPosition, File::*, Rank::*};
-use crate::board::{Board, Turn, MoveType, GameState};
+use crate::board::{Board, Turn, MoveType, GameState, GameResult, Termination};
 use crate::board::check::is_in_check;
+use crate::chess_move::ChessMove;
 use crate::piece::{Color, Piece, get_king_moves, get_pawn_moves, PawnState};
 use crate::piece::shorthands::*;
+use crate::variant::Variant;
 
 #[derive(Debug)]
 pub enum ChessError {
@@ -21,6 +34,7 @@ pub enum ChessError {
     InvalidMove,
     SelfCheck,
     IncorrectFenString,
+    UnknownVariant,
     InternalError,
 }
 
@@ -29,23 +43,28 @@ pub enum ChessError {
 /// The squares can be accessed by either indexing or iteration
 ///
 /// ```rust
+/// use viktoe_chess::position;
+/// use viktoe_chess::prelude::*;
+///
 /// let game = ChessGame::default();
 /// let valid_moves = game.get_valid_moves(&(E, Two).into());
 ///
-/// for file in File.iter() {
-///     for rank in Rank.iter() {
-///         match game.get_square((file, rank)) {
-///             ...
-///         }
+/// for position in position::iter() {
+///     match game.get_square(&position) {
+///         Some(_) => assert!(matches!(position.get_rank(), One | Two | Seven | Eight)),
+///         None => assert!(matches!(position.get_rank(), Three | Four | Five | Six)),
+///     }
 ///
-///         match valid_moves.get((file, rank)) {
-///             ...
-///         }
+///     match valid_moves.get(&position) {
+///         Some(_) => assert_eq!(position.get_file(), &E),
+///         None => {}
 ///     }
 /// }
 /// ```
 ///
 /// ```rust
+/// use viktoe_chess::prelude::*;
+///
 /// let game = ChessGame::default();
 /// let valid_moves = game.get_valid_moves(&(E, Two).into());
 ///
@@ -55,43 +74,111 @@ pub enum ChessError {
 ///     let (piece, move_type) = square;
 ///
 ///     match piece {
-///         ...
+///         Some(_) => assert!(move_type.is_none()),
+///         None => {}
 ///     }
 ///
 ///     match move_type {
-///         ...
+///         Some(MoveType::Move) => assert!(piece.is_none()),
+///         Some(MoveType::Capture) | Some(MoveType::Drop) => unreachable!(),
+///         None => {}
 ///     }
 /// }
 /// ```
+#[derive(Clone)]
 pub struct ChessGame {
     pub(crate) board: Board<Color<Piece>>,
     turn: Turn,
     state: GameState,
-    white_king_position: BoardPosition,
-    black_king_position: BoardPosition,
+    white_king_position: Option<BoardPosition>,
+    black_king_position: Option<BoardPosition>,
     en_passant: Vec<BoardPosition>,
     half_move: u8,
-    full_move: u8,
-    white_possition_history: HashMap<Board<Color<Piece>>, u8>,
-    black_possition_history: HashMap<Board<Color<Piece>>, u8>,
+    full_move: u16,
+    white_checks: u8,
+    black_checks: u8,
+    white_pocket: Vec<Piece>,
+    black_pocket: Vec<Piece>,
+    promoted: Vec<BoardPosition>,
+    // The history is shared between clones until one of them makes a move, so cloning a game is
+    // cheap however long it is
+    white_possition_history: Arc<HashMap<Board<Color<Piece>>, u8>>,
+    black_possition_history: Arc<HashMap<Board<Color<Piece>>, u8>>,
+    moves: Arc<Vec<ChessMove>>,
+    previous_positions: Arc<Vec<ChessGame>>,
+    variant: Arc<dyn Variant>,
+    // Whether the player whos turn it is can capture, found once per position for variants where
+    // captures are compulsory and cleared whenever a piece moves
 }
 
 impl ChessGame {
+    /// Creates a new game played with the rules of `variant`
+    pub fn new(variant: impl Variant + 'static) -> Self {
+        let board = variant.start_position();
+
+        let white_king_position = board.find_king(&Turn::White);
+        let black_king_position = board.find_king(&Turn::Black);
+
+        Self {
+            board,
+            turn: Turn::White,
+            state: GameState::Ongoing,
+            white_king_position,
+            black_king_position,
+            en_passant: Vec::new(),
+            half_move: 0,
+            full_move: 1,
+            white_checks: 0,
+            black_checks: 0,
+            white_pocket: Vec::new(),
+            black_pocket: Vec::new(),
+            promoted: Vec::new(),
+            white_possition_history: Arc::default(),
+            black_possition_history: Arc::default(),
+            moves: Arc::default(),
+            previous_positions: Arc::default(),
+            variant: Arc::new(variant),
+        }
+    }
+
+    /// Returns the rules the game is played with
+    pub fn get_variant(&self) -> &dyn Variant {
+        self.variant.as_ref()
+    }
+
     /// Returns a reference to a specific square on the board
     pub fn get_square(&self, position: &BoardPosition) -> &Option<Color<Piece>> {
         self.board.get(position)
     }
 
-    pub(crate) fn get_king_position(&self, player_color: &Turn) -> &BoardPosition {
+    /// Returns the position of the king of a player, or None if the player has no king
+    pub fn get_king_position(&self, player_color: &Turn) -> Option<&BoardPosition> {
         match player_color {
-            Turn::White => &self.white_king_position,
-            Turn::Black => &self.black_king_position,
+            Turn::White => self.white_king_position.as_ref(),
+            Turn::Black => self.black_king_position.as_ref(),
+        }
+    }
+
+    /// Returns true if the king of the player is in check, a player without a king is never in
+    /// check
+    pub fn is_in_check(&self, player_color: &Turn) -> bool {
+        match self.get_king_position(player_color) {
+            Some(king_position) => is_in_check(&self.board, king_position, player_color),
+            None => false,
         }
     }
 
     /// Returns a Board containing moves that can be made from a square if moves can be made from
     /// that square.
     pub fn get_valid_moves(&self, position: &BoardPosition) -> Board<MoveType> {
+        let moves = self.get_unfiltered_moves(position);
+
+        self.variant.filter_moves(self, position, moves)
+    }
+
+    /// Returns the moves that can be made from a square without the restrictions a variant places
+    /// on the moves of the other pieces, such as compulsory captures.
+    pub fn get_unfiltered_moves(&self, position: &BoardPosition) -> Board<MoveType> {
         let piece = match self.board.get(position).as_ref() {
             Some(piece) => piece,
             None => return Board::default(),
@@ -101,15 +188,85 @@ impl ChessGame {
             return Board::default();
         }
 
-        let king_position = self.get_king_position(&self.turn);
-
-        match piece.get_internal() {
-            Piece::King { castling_state, .. } => {
+        let mut moves = match piece.get_internal() {
+            Piece::King { castling_state, .. } if self.variant.allows_castling() => {
                 get_king_moves(&self.board, position, piece, castling_state, &self.turn)
             }
+            Piece::King { .. } => get_king_moves(&self.board, position, piece, &(false, false), &self.turn),
             Piece::Pawn { .. } => get_pawn_moves(&self.board, position, piece),
-            _ => piece.get_standard_valid_move(&self.board, position, king_position, &self.turn),
+            _ => piece.get_standard_moves(&self.board, position, &self.turn),
+        };
+
+        // Remove the moves the variant does not allow
+        for desired_position in position::iter() {
+            let move_type = match moves.get(&desired_position) {
+                Some(move_type) => move_type.clone(),
+                None => continue,
+            };
+
+            let mut test_game = self.clone_position();
+            test_game.perform_move(position, &desired_position, &move_type);
+
+            if !self.variant.is_legal(&test_game, &self.turn) {
+                moves.set(&desired_position, None);
+            }
+        }
+
+        moves
+    }
+
+    /// Returns true if the player whos turn it is can make any move
+    pub fn has_valid_moves(&self) -> bool {
+        self.board
+            .iter()
+            .zip(position::iter())
+            .filter(|(piece, _)| {
+                if let Some(piece) = piece.as_ref() {
+                    piece.same_color(&self.turn)
+                } else {
+                    false
+                }
+            })
+            .any(|(_, position)| {
+                self.get_valid_moves(&position)
+                    .iter()
+                    .any(|square| square.is_some())
+            })
+            || self.get_pocket(&self.turn).iter().any(|piece| {
+                self.get_valid_drops(piece)
+                    .iter()
+                    .any(|square| square.is_some())
+            })
+    }
+
+    /// Returns a Board containing the squares a piece from the pocket of the player whos turn it
+    /// is can be dropped on.
+    pub fn get_valid_drops(&self, piece: &Piece) -> Board<MoveType> {
+        let mut drops = Board::default();
+
+        if !self.get_pocket(&self.turn).contains(piece) {
+            return drops;
+        }
+
+        for position in position::iter() {
+            if self.board.get(&position).is_some() {
+                continue;
+            }
+
+            // Pawns can not be dropped on the first or last rank
+            if matches!(piece, Piece::Pawn { .. }) && matches!(position.get_rank(), One | Eight) {
+                continue;
+            }
+
+            let mut test_game = self.clone_position();
+            test_game.perform_drop(piece, &position);
+
+            if self.variant.is_legal(&test_game, &self.turn) {
+                drops.set(&position, Some(MoveType::Drop));
+            }
         }
+
+        drops
     }
 
     /// Moves a piece from one square to another
@@ -120,7 +277,7 @@ impl ChessGame {
         initial_position: &BoardPosition,
         desired_position: &BoardPosition,
     ) -> Result<GameState, ChessError> {
-        let mut piece = self
+        let piece = self
             .board
             .get(initial_position)
             .clone()
@@ -136,24 +293,301 @@ impl ChessGame {
 
         let move_type = moves
             .get(desired_position)
-            .as_ref()
+            .clone()
             .ok_or(ChessError::InvalidMove)?;
 
+        let previous_position = self.clone_position();
+        Arc::make_mut(&mut self.previous_positions).push(previous_position);
+        Arc::make_mut(&mut self.moves).push(ChessMove::Move {
+            from: initial_position.clone(),
+            to: desired_position.clone(),
+            promotion: None,
+        });
+
+        self.perform_move(initial_position, desired_position, &move_type);
+
+        // The moved piece is not on the board if a variant removed it during the move
+        self.state = match self.board.get(desired_position).clone() {
+            Some(piece)
+                if matches!(piece.get_internal(), Piece::Pawn { .. })
+                    && ((matches!(self.turn, Turn::White) && matches!(desired_position.get_rank(), Eight))
+                        | (matches!(self.turn, Turn::Black) && matches!(desired_position.get_rank(), One))) =>
+            {
+                GameState::Promotion(desired_position.clone(), piece, move_type.clone())
+            }
+            _ => self.progress_turn(piece.get_internal(), &move_type),
+        };
+
+        Ok(self.state.clone())
+    }
+
+    /// Places a piece from the pocket of the player on an empty square
+    ///
+    /// Fails with custom errors explaining why the piece cannot be dropped
+    pub fn drop_piece(
+        &mut self,
+        piece: Piece,
+        desired_position: &BoardPosition,
+    ) -> Result<GameState, ChessError> {
+        if !self.get_pocket(&self.turn).contains(&piece) {
+            return Err(ChessError::NoPiece);
+        }
+
+        if self.get_valid_drops(&piece).get(desired_position).is_none() {
+            return Err(ChessError::InvalidMove);
+        }
+
+        let previous_position = self.clone_position();
+        Arc::make_mut(&mut self.previous_positions).push(previous_position);
+        Arc::make_mut(&mut self.moves).push(ChessMove::Drop {
+            piece: piece.clone(),
+            to: desired_position.clone(),
+        });
+
+        self.perform_drop(&piece, desired_position);
+
+        self.state = self.progress_turn(&piece, &MoveType::Drop);
+
+        Ok(self.state.clone())
+    }
+
+    fn progress_turn(&mut self, piece: &Piece, move_type: &MoveType) -> GameState {
+        // Comply with repeated position
+        let history = match self.turn {
+            Turn::White => Arc::make_mut(&mut self.white_possition_history),
+            Turn::Black => Arc::make_mut(&mut self.black_possition_history),
+        };
+
+        *history.entry(self.board.clone()).or_insert(0) += 1;
+
+        // Move to next move
+        self.turn = match self.turn {
+            Turn::White => Turn::Black,
+            Turn::Black => Turn::White,
+        };
+
+        // The full move counter is increased after black has moved
+        if matches!(self.turn, Turn::White) {
+            self.full_move += 1;
+        }
+
+        // Performe check to comply with 50-move draw rule
+        if matches!(move_type, MoveType::Capture) || matches!(piece, Piece::Pawn { .. }) {
+            self.half_move = 0;
+        } else {
+            self.half_move += 1;
+        }
+
+        // Count the checks given by each player, used by variants such as three-check
+        if self.is_in_check(&self.turn) {
+            match self.turn {
+                Turn::White => self.black_checks += 1,
+                Turn::Black => self.white_checks += 1,
+            }
+        }
+
+        let variant = Arc::clone(&self.variant);
+        self.state = variant.game_state(self);
+
+        self.state.clone()
+    }
+
+    /// If a specific position has been repeated 3 times in a game, a player can request a draw.
+    /// The function will return true if 3 repeated positions has occured.
+    pub fn request_draw_due_to_repeated_position(&self) -> bool {
+        self.white_possition_history
+            .values()
+            .any(|value| *value >= 3)
+            || self
+                .black_possition_history
+                .values()
+                .any(|value| *value >= 3)
+    }
+
+    /// Takes back the last move, returning the move that was taken back.
+    ///
+    /// Fails with NoMoves if no moves have been made since the game was created.
+    pub fn undo_move(&mut self) -> Result<ChessMove, ChessError> {
+        let previous_position = Arc::make_mut(&mut self.previous_positions)
+            .pop()
+            .ok_or(ChessError::NoMoves)?;
+        let chess_move = Arc::make_mut(&mut self.moves).pop().ok_or(ChessError::InternalError)?;
+
+        // The position after the move is only counted once the turn has passed on
+        if !matches!(self.state, GameState::Promotion(..)) {
+            let history = match previous_position.turn {
+                Turn::White => Arc::make_mut(&mut self.white_possition_history),
+                Turn::Black => Arc::make_mut(&mut self.black_possition_history),
+            };
+
+            if let Some(amount) = history.get_mut(&self.board) {
+                *amount -= 1;
+
+                if *amount == 0 {
+                    history.remove(&self.board);
+                }
+            }
+        }
+
+        *self = Self {
+            white_possition_history: mem::take(&mut self.white_possition_history),
+            black_possition_history: mem::take(&mut self.black_possition_history),
+            moves: mem::take(&mut self.moves),
+            previous_positions: mem::take(&mut self.previous_positions),
+            ..previous_position
+        };
+
+        Ok(chess_move)
+    }
+
+    /// Returns the moves made since the game was created, starting with the first move
+    pub fn get_moves(&self) -> &[ChessMove] {
+        &self.moves
+    }
+
+    /// Promotes a pawn on the final rank, will return invalid move if there is no pawn to promote.
+    pub fn promote_pawn(&mut self, promotion_target: Piece) -> Result<GameState, ChessError> {
+        let (pawn_position, piece, move_type) = match &self.state {
+            GameState::Promotion(position, piece, move_type) => {
+                (position.clone(), piece.clone(), move_type.clone())
+            }
+            _ => return Err(ChessError::InvalidMove),
+        };
+
+        if !self.variant.promotion_pieces().contains(&promotion_target) {
+            return Err(ChessError::InvalidMove);
+        }
+
+        // Checking pawn of same color has players turn on correct row (pawn cannot get to the
+        // first or last rank exept on the other side)
+        if matches!(piece.get_internal(), Piece::Pawn { .. })
+            && piece.same_color(&self.turn)
+            && (matches!(pawn_position.rank, Eight) || matches!(pawn_position.rank, One))
+        {
+            let mut promoted_piece = piece.clone();
+            promoted_piece.change_internal(promotion_target.clone());
+
+            if let Some(ChessMove::Move { promotion, .. }) = Arc::make_mut(&mut self.moves).last_mut() {
+                *promotion = Some(promotion_target);
+            }
+
+            self.board.set(&pawn_position, Some(promoted_piece));
+            self.promoted.push(pawn_position);
+        }
+
+        Ok(self.progress_turn(piece.get_internal(), &move_type))
+    }
+
+    pub fn get_player_turn(&self) -> &Turn {
+        &self.turn
+    }
+
+    pub fn get_game_state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Returns the winner and the reason the game ended, or None if the game has not ended
+    pub fn get_result(&self) -> Option<GameResult> {
+        match &self.state {
+            GameState::CheckMate => Some(GameResult {
+                winner: Some(self.turn.opponent()),
+                termination: Termination::CheckMate,
+            }),
+            GameState::Draw(termination) => Some(GameResult {
+                winner: None,
+                termination: termination.clone(),
+            }),
+            GameState::Win(winner, termination) => Some(GameResult {
+                winner: Some(winner.clone()),
+                termination: termination.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the pieces a player has captured and can drop on the board in variants with pockets
+    pub fn get_pocket(&self, player_color: &Turn) -> &[Piece] {
+        match player_color {
+            Turn::White => &self.white_pocket,
+            Turn::Black => &self.black_pocket,
+        }
+    }
+
+    pub(crate) fn add_to_pocket(&mut self, player_color: &Turn, piece: Piece) {
+        match player_color {
+            Turn::White => self.white_pocket.push(piece),
+            Turn::Black => self.black_pocket.push(piece),
+        }
+    }
+
+    /// Returns true if the piece on the position was a pawn that has been promoted
+    pub fn is_promoted(&self, position: &BoardPosition) -> bool {
+        self.promoted.contains(position)
+    }
+
+    /// Returns the number of times a player has put the other player in check
+    pub fn get_checks_given(&self, player_color: &Turn) -> u8 {
+        match player_color {
+            Turn::White => self.white_checks,
+            Turn::Black => self.black_checks,
+        }
+    }
+}
+
+impl ChessGame {
+    // Clones the game without the history of earlier positions, used to test the outcome of moves
+    fn clone_position(&self) -> Self {
+        Self {
+            board: self.board.clone(),
+            turn: self.turn.clone(),
+            state: self.state.clone(),
+            white_king_position: self.white_king_position.clone(),
+            black_king_position: self.black_king_position.clone(),
+            en_passant: self.en_passant.clone(),
+            half_move: self.half_move,
+            full_move: self.full_move,
+            white_checks: self.white_checks,
+            black_checks: self.black_checks,
+            white_pocket: self.white_pocket.clone(),
+            black_pocket: self.black_pocket.clone(),
+            promoted: self.promoted.clone(),
+            white_possition_history: Arc::default(),
+            black_possition_history: Arc::default(),
+            moves: Arc::default(),
+            previous_positions: Arc::default(),
+            variant: Arc::clone(&self.variant),
+        }
+    }
+
+    // Moves the piece without checking if the move is valid or progressing the turn
+    fn perform_move(
+        &mut self,
+        initial_position: &BoardPosition,
+        desired_position: &BoardPosition,
+        move_type: &MoveType,
+    ) {
+        let mut piece = match self.board.get(initial_position).clone() {
+            Some(piece) => piece,
+            None => return,
+        };
+
+        let mut captured = self.board.get(desired_position).clone();
+
         // Move rook during castling
         if matches!(piece.get_internal(), Piece::King { .. })
             && (i32::from(u8::from(initial_position.file.clone())) - i32::from(u8::from(desired_position.file.clone()))).abs() >= 2 {
             if matches!(desired_position, BoardPosition { file: G, rank: One}) {
                 self.board.set(&BoardPosition::from((H, One)), None);
                 self.board.set(&BoardPosition::from((F, One)), Some(WHITE_ROOK));
-            } else if matches!(desired_position, BoardPosition { file: B, rank: One}) {
+            } else if matches!(desired_position, BoardPosition { file: C, rank: One}) {
                 self.board.set(&BoardPosition::from((A, One)), None);
-                self.board.set(&BoardPosition::from((C, One)), Some(WHITE_ROOK));
+                self.board.set(&BoardPosition::from((D, One)), Some(WHITE_ROOK));
             } else if matches!(desired_position, BoardPosition { file: G, rank: Eight}) {
                 self.board.set(&BoardPosition::from((H, Eight)), None);
                 self.board.set(&BoardPosition::from((F, Eight)), Some(BLACK_ROOK));
-            } else if matches!(desired_position, BoardPosition { file: B, rank: Eight}) {
+            } else if matches!(desired_position, BoardPosition { file: C, rank: Eight}) {
                 self.board.set(&BoardPosition::from((A, Eight)), None);
-                self.board.set(&BoardPosition::from((C, Eight)), Some(BLACK_ROOK));
+                self.board.set(&BoardPosition::from((D, Eight)), Some(BLACK_ROOK));
             }
         }
 
@@ -175,20 +609,17 @@ impl ChessGame {
             && matches!(piece.get_internal(), Piece::Pawn { .. })
             && self.get_square(desired_position).is_none()
         {
-            match self.turn {
-                Turn::White => self.board.set(
-                    &desired_position
-                        .add((0, -1))
-                        .expect("Taking by en passant, captured piece should be on board"),
-                    None,
-                ),
-                Turn::Black => self.board.set(
-                    &desired_position
-                        .add((0, 1))
-                        .expect("Taking by en passant, captured piece should be on board"),
-                    None,
-                ),
-            }
+            let captured_position = match self.turn {
+                Turn::White => desired_position
+                    .add((0, -1))
+                    .expect("Taking by en passant, captured piece should be on board"),
+                Turn::Black => desired_position
+                    .add((0, 1))
+                    .expect("Taking by en passant, captured piece should be on board"),
+            };
+
+            captured = self.board.get(&captured_position).clone();
+            self.board.set(&captured_position, None);
         }
 
         self.en_passant = Vec::new();
@@ -208,147 +639,106 @@ impl ChessGame {
             piece.change_internal(Piece::Pawn {
                 state: PawnState::PosibleEnPassant,
             })
+        } else if matches!(piece.get_internal(), Piece::Pawn { .. }) {
+            // A pawn that has moved can not move two squares anymore
+            piece.change_internal(Piece::Pawn {
+                state: PawnState::Default,
+            })
         }
 
         if matches!(piece.get_internal(), Piece::King { .. }) {
             match self.turn {
-                Turn::White => self.white_king_position = desired_position.clone(),
-                Turn::Black => self.black_king_position = desired_position.clone(),
+                Turn::White => self.white_king_position = Some(desired_position.clone()),
+                Turn::Black => self.black_king_position = Some(desired_position.clone()),
             }
         }
 
         // Performe move
         self.board.set(initial_position, None);
-        self.board.set(desired_position, Some(piece.clone()));
-
-        self.state = if matches!(piece.get_internal(), Piece::Pawn { .. })
-            && ((matches!(self.turn, Turn::White) && matches!(desired_position.get_rank(), Eight))
-                | (matches!(self.turn, Turn::Black) && matches!(desired_position.get_rank(), One)))
-        {
-            GameState::Promotion(desired_position.clone(), piece.clone(), move_type.clone())
-        } else {
-            self.progress_turn(piece.get_internal(), move_type)
-        };
-
-        Ok(self.state.clone())
-    }
-
-    fn progress_turn(&mut self, piece: &Piece, move_type: &MoveType) -> GameState {
-        // Comply with repeated position
-        match self.turn {
-            Turn::White => {
-                match self.white_possition_history.get(&self.board) {
-                    Some(amount) => self
-                        .white_possition_history
-                        .insert(self.board.clone(), amount + 1),
-                    None => self.white_possition_history.insert(self.board.clone(), 1),
-                };
-            }
-            Turn::Black => {
-                match self.black_possition_history.get(&self.board) {
-                    Some(amount) => self
-                        .black_possition_history
-                        .insert(self.board.clone(), amount + 1),
-                    None => self.black_possition_history.insert(self.board.clone(), 1),
-                };
-            }
-        };
-
-        // Move to next move
-        self.turn = match self.turn {
-            Turn::White => Turn::Black,
-            Turn::Black => Turn::White,
-        };
+        self.board.set(desired_position, Some(piece));
 
-        self.full_move += 1;
+        let variant = Arc::clone(&self.variant);
+        variant.after_move(self, initial_position, desired_position, move_type, &captured);
 
-        // Performe check to comply with 50-move draw rule
-        if matches!(move_type, MoveType::Capture) || matches!(piece, Piece::Pawn { .. }) {
-            self.half_move = 0;
-        } else {
-            self.half_move += 1;
+        // Kings can be captured or removed by the variant
+        if !matches!(
+            self.white_king_position.as_ref().map(|position| self.board.get(position)),
+            Some(Some(Color::White(Piece::King { .. })))
+        ) {
+            self.white_king_position = None;
+        }
+        if !matches!(
+            self.black_king_position.as_ref().map(|position| self.board.get(position)),
+            Some(Some(Color::Black(Piece::King { .. })))
+        ) {
+            self.black_king_position = None;
         }
 
-        self.state = if is_in_check(&self.board, self.get_king_position(&self.turn), &self.turn) {
-            // If the player whos turn it is next can move any piece they are not in mate
-            if self
-                .board
-                .board
-                .iter()
-                .zip(position::iter())
-                .filter(|(piece, _)| {
-                    if let Some(piece) = piece.as_ref() {
-                        piece.same_color(&self.turn)
-                    } else {
-                        false
-                    }
-                })
-                .any(|(_, position)| self.get_valid_moves(&position).iter().any(|square| square.is_some()))
-            {
-                GameState::Check
-            } else {
-                GameState::CheckMate
-            }
-        } else if self.half_move >= 100 {
-            GameState::Draw
-        } else {
-            GameState::Ongoing
-        };
+        // Keep track of promoted pieces, done after the variant so it can see if the captured piece
+        // was promoted
+        self.promoted.retain(|position| position != desired_position);
 
-        self.state.clone()
+        if let Some(index) = self.promoted.iter().position(|position| position == initial_position) {
+            self.promoted[index] = desired_position.clone();
+        }
     }
 
-    /// If a specific position has been repeated 3 times in a game, a player can request a draw.
-    /// The function will return true if 3 repeated positions has occured.
-    pub fn request_draw_due_to_repeated_position(&self) -> bool {
-        self.white_possition_history
-            .values()
-            .any(|value| *value >= 3)
-            || self
-                .black_possition_history
-                .values()
-                .any(|value| *value >= 3)
-    }
+    // Drops the piece without checking if the drop is valid or progressing the turn
+    fn perform_drop(&mut self, piece: &Piece, desired_position: &BoardPosition) {
+        let pocket = match self.turn {
+            Turn::White => &mut self.white_pocket,
+            Turn::Black => &mut self.black_pocket,
+        };
 
-    /// Promotes a pawn on the final rank, will return invalid move if there is no pawn to promote.
-    pub fn promote_pawn(&mut self, promotion_target: Piece) -> Result<GameState, ChessError> {
-        let (pawn_position, piece, move_type) = match &mut self.state {
-            GameState::Promotion(position, piece, move_type) => (position, piece, move_type),
-            _ => return Err(ChessError::InvalidMove),
+        match pocket.iter().position(|pocket_piece| pocket_piece == piece) {
+            Some(index) => pocket.swap_remove(index),
+            None => return,
         };
 
-        // Checking pawn of same color has players turn on correct row (pawn cannot get to the
-        // first or last rank exept on the other side)
-        if matches!(piece.get_internal(), Piece::Pawn { .. })
-            && piece.same_color(&self.turn)
-            && (matches!(pawn_position.rank, Eight) || matches!(pawn_position.rank, One))
-        {
-            piece.change_internal(promotion_target)
+        // Update list of pawn that can be taken using en passant
+        for position in &self.en_passant {
+            if let Some(pawn) = self.board.get_mut(position).as_mut() {
+                pawn.change_internal(Piece::Pawn {
+                    state: PawnState::Default,
+                })
+            }
         }
 
-        let piece = piece.clone();
-        let move_type = move_type.clone();
+        self.en_passant = Vec::new();
 
-        Ok(self.progress_turn(piece.get_internal(), &move_type))
-    }
+        // Pawns dropped on their starting rank can move two squares
+        let piece = match (piece, &self.turn, desired_position.get_rank()) {
+            (Piece::Pawn { .. }, Turn::White, Two) | (Piece::Pawn { .. }, Turn::Black, Seven) => {
+                Piece::Pawn {
+                    state: PawnState::FirstMove,
+                }
+            }
+            (Piece::Pawn { .. }, _, _) => Piece::Pawn {
+                state: PawnState::Default,
+            },
+            (piece, _, _) => piece.clone(),
+        };
 
-    pub fn get_player_turn(&self) -> &Turn {
-        &self.turn
-    }
+        let piece = match self.turn {
+            Turn::White => Color::White(piece),
+            Turn::Black => Color::Black(piece),
+        };
 
-    pub fn get_game_state(&self) -> &GameState {
-        &self.state
+        self.promoted.retain(|position| position != desired_position);
+        self.board.set(desired_position, Some(piece));
     }
-}
 
-impl ChessGame {
-    // If king_position is out of sync will not remove
+    // If king_position is out of sync will not remove, a player without a king has no castling
+    // options to remove
     fn remove_castling_options(
         &mut self,
         piece: &mut Color<Piece>,
         initial_position: &BoardPosition,
     ) -> Result<(), ChessError> {
-        let king_position = self.get_king_position(&self.turn).clone();
+        let king_position = match self.get_king_position(&self.turn) {
+            Some(king_position) => king_position.clone(),
+            None => return Ok(()),
+        };
         let king = self
             .board
             .get_mut(&king_position)
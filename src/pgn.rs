@@ -0,0 +1,276 @@
+//! Reading games in Portable Game Notation.
+//!
+//! Only the main line of each game is kept, comments, variations and numeric annotation glyphs
+//! are skipped.
+//!
+//! ```rust
+//! use viktoe_chess::pgn;
+//!
+//! let games = pgn::parse("[White \"Morphy\"]\n\n1. e4 e5 2. Nf3 d6 1-0");
+//!
+//! assert_eq!(games[0].get_tag("White"), Some("Morphy"));
+//! assert_eq!(games[0].moves, ["e4", "e5", "Nf3", "d6"]);
+//! assert_eq!(games[0].to_game().unwrap().get_moves().len(), 4);
+//! ```
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::variant::{Antichess, Atomic, Crazyhouse, Horde, KingOfTheHill, RacingKings, Standard, ThreeCheck, Variant};
+use crate::{ChessError, ChessGame};
+
+/// A game read from PGN
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgnGame {
+    /// The tag pairs in the order they were read, such as `("White", "Morphy")`
+    pub tags: Vec<(String, String)>,
+    /// The moves of the main line in standard algebraic notation
+    pub moves: Vec<String>,
+    /// `1-0`, `0-1`, `1/2-1/2` or `*` if the result is unknown
+    pub result: String,
+}
+
+impl PgnGame {
+    /// Returns the value of a tag
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the position the game starts in, using the `Variant` and `FEN` tags if they are
+    /// present
+    pub fn get_start_position(&self) -> Result<ChessGame, ChessError> {
+        let variant = self.get_tag("Variant").unwrap_or("standard").to_ascii_lowercase();
+        let fen = self.get_tag("FEN").map(String::from);
+
+        match variant.as_str() {
+            "standard" | "chess" | "normal" | "from position" => start_position(Standard, fen),
+            "antichess" | "giveaway" | "suicide" => start_position(Antichess, fen),
+            "atomic" => start_position(Atomic, fen),
+            "crazyhouse" => start_position(Crazyhouse, fen),
+            "horde" => start_position(Horde, fen),
+            "king of the hill" | "kingofthehill" => start_position(KingOfTheHill, fen),
+            "racing kings" | "racingkings" => start_position(RacingKings, fen),
+            "three-check" | "threecheck" | "3check" => start_position(ThreeCheck, fen),
+            _ => Err(ChessError::UnknownVariant),
+        }
+    }
+
+    /// Plays the moves of the game from its start position, failing at the first illegal move
+    pub fn to_game(&self) -> Result<ChessGame, ChessError> {
+        let mut game = self.get_start_position()?;
+
+        for san in &self.moves {
+            let chess_move = game.parse_san(san)?;
+            game.make_move(&chess_move)?;
+        }
+
+        Ok(game)
+    }
+}
+
+/// Reads every game of a PGN file
+pub fn parse(text: &str) -> Vec<PgnGame> {
+    let mut games = Vec::new();
+    let mut game = PgnGame::default();
+    let mut chars = text.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '[' => {
+                // A tag after the moves starts a new game, the last one had no result
+                if !game.moves.is_empty() {
+                    games.push(finish(game));
+                    game = PgnGame::default();
+                }
+
+                if let Some(tag) = read_tag(&mut chars) {
+                    game.tags.push(tag);
+                }
+            }
+            '{' => skip_comment(&mut chars),
+            ';' | '%' => {
+                for char in chars.by_ref() {
+                    if char == '\n' {
+                        break;
+                    }
+                }
+            }
+            '(' => skip_variation(&mut chars),
+            '$' => {
+                while chars.next_if(char::is_ascii_digit).is_some() {}
+            }
+            char if char.is_whitespace() => {}
+            char => {
+                let mut token = String::from(char);
+
+                while let Some(char) = chars.next_if(|char| !char.is_whitespace() && !"[]{}();".contains(*char)) {
+                    token.push(char);
+                }
+
+                match token.as_str() {
+                    "1-0" | "0-1" | "1/2-1/2" | "*" => {
+                        game.result = token;
+                        games.push(finish(game));
+                        game = PgnGame::default();
+                    }
+                    _ => {
+                        // Remove the move number, as in `12.` or `12...e5`
+                        let san = match token.split_once('.') {
+                            Some((number, san)) if number.chars().all(|char| char.is_ascii_digit()) => {
+                                san.trim_start_matches('.')
+                            }
+                            _ => &token,
+                        };
+
+                        if !san.is_empty() {
+                            game.moves.push(san.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if !game.tags.is_empty() || !game.moves.is_empty() {
+        games.push(finish(game));
+    }
+
+    games
+}
+
+fn start_position(variant: impl Variant + 'static, fen: Option<String>) -> Result<ChessGame, ChessError> {
+    match fen {
+        Some(fen) => ChessGame::from_fen_with_variant(fen, variant),
+        None => Ok(ChessGame::new(variant)),
+    }
+}
+
+fn finish(mut game: PgnGame) -> PgnGame {
+    if game.result.is_empty() {
+        game.result = game.get_tag("Result").unwrap_or("*").to_string();
+    }
+
+    game
+}
+
+// Reads `Name "Value"]` after the opening bracket
+fn read_tag(chars: &mut Peekable<Chars>) -> Option<(String, String)> {
+    let mut name = String::new();
+    let mut value = String::new();
+
+    while let Some(char) = chars.next_if(|char| *char != '"' && *char != ']') {
+        name.push(char);
+    }
+
+    if chars.next_if_eq(&'"').is_some() {
+        while let Some(char) = chars.next() {
+            match char {
+                '\\' => value.extend(chars.next()),
+                '"' => break,
+                char => value.push(char),
+            }
+        }
+    }
+
+    for char in chars.by_ref() {
+        if char == ']' {
+            break;
+        }
+    }
+
+    let name = name.trim();
+
+    (!name.is_empty()).then(|| (name.to_string(), value))
+}
+
+fn skip_comment(chars: &mut Peekable<Chars>) {
+    for char in chars.by_ref() {
+        if char == '}' {
+            break;
+        }
+    }
+}
+
+// Skips a variation after the opening parenthesis, including the variations inside it
+fn skip_variation(chars: &mut Peekable<Chars>) {
+    let mut depth = 1;
+
+    while let Some(char) = chars.next() {
+        match char {
+            '{' => skip_comment(chars),
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAMES: &str = r#"
+[Event "Casual \"Game\""]
+[White "Anderssen"]
+[Result "1-0"]
+
+1. e4 {A comment (with a parenthesis)} e5 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) 2...Nc6 $1
+; A comment to the end of the line
+3.Bb5 a6!? 1-0
+
+[Variant "Crazyhouse"]
+
+1. e4 d5 2. exd5 Qxd5 3. P@e6
+
+[Event "No moves"]
+*
+"#;
+
+    #[test]
+    fn parses_games() {
+        let games = parse(GAMES);
+
+        assert_eq!(games.len(), 3);
+
+        assert_eq!(games[0].get_tag("Event"), Some("Casual \"Game\""));
+        assert_eq!(games[0].moves, ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6!?"]);
+        assert_eq!(games[0].result, "1-0");
+
+        assert_eq!(games[1].moves.len(), 5);
+        assert_eq!(games[1].result, "*");
+
+        assert!(games[2].moves.is_empty());
+        assert_eq!(games[2].get_tag("Event"), Some("No moves"));
+    }
+
+    #[test]
+    fn plays_games() {
+        let games = parse(GAMES);
+
+        assert_eq!(
+            games[0].to_game().unwrap().to_fen(),
+            "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 0 4"
+        );
+
+        let game = games[1].to_game().unwrap();
+
+        assert_eq!(game.get_variant().name(), "crazyhouse");
+        assert_eq!(game.get_moves().len(), 5);
+
+        let illegal = PgnGame {
+            moves: vec![String::from("e5")],
+            ..PgnGame::default()
+        };
+
+        assert!(illegal.to_game().is_err());
+    }
+}
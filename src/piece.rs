@@ -113,11 +113,12 @@ impl<T> Color<T> {
 }
 
 impl Color<Piece> {
-    pub(crate) fn get_standard_valid_move(
+    // Returns every square the piece can reach without considering if the move leaves the king in
+    // check
+    pub(crate) fn get_standard_moves(
         &self,
         board: &Board<Color<Piece>>,
         position: &BoardPosition,
-        king_position: &BoardPosition,
         player_color: &Turn,
     ) -> Board<MoveType> {
         let mut move_map = Board::default();
@@ -125,24 +126,38 @@ impl Color<Piece> {
         for base_vector in self.get_movement_base_vector() {
             let number_of_steps = self.get_number_of_moves().into();
 
-            // Get all squares the piece can move to and remove the ones that cause the king to be
-            // in check
             for (position, move_type) in
                 evaluate_vector(board, base_vector, number_of_steps, player_color, position)
-                    .into_iter()
-                    .map(|(new_position, move_type)| {
-                        let mut test_board = board.clone();
-                        test_board.set(&new_position, Some(self.clone()));
-                        test_board.set(position, None);
-
-                        if is_in_check(&test_board, king_position, player_color) {
-                            (new_position, None)
-                        } else {
-                            (new_position, Some(move_type))
-                        }
-                    })
             {
-                move_map.set(&position, move_type);
+                move_map.set(&position, Some(move_type));
+            }
+        }
+        move_map
+    }
+
+    /// Returns the squares the piece can move to without leaving the king on `king_position` in
+    /// check, does not handle castling, en passant or the rules of variants.
+    pub fn get_standard_valid_move(
+        &self,
+        board: &Board<Color<Piece>>,
+        position: &BoardPosition,
+        king_position: &BoardPosition,
+        player_color: &Turn,
+    ) -> Board<MoveType> {
+        let mut move_map = self.get_standard_moves(board, position, player_color);
+
+        // Remove the squares that cause the king to be in check
+        for new_position in crate::position::iter() {
+            if move_map.get(&new_position).is_none() {
+                continue;
+            }
+
+            let mut test_board = board.clone();
+            test_board.set(&new_position, Some(self.clone()));
+            test_board.set(position, None);
+
+            if is_in_check(&test_board, king_position, player_color) {
+                move_map.set(&new_position, None);
             }
         }
         move_map
@@ -206,7 +221,7 @@ mod tests {
     use super::*;
     use crate::board::{MoveType, Turn};
     use crate::board::Board;
-    use crate::position::{BoardPosition, File::*, Rank::*, FILE, RANK};
+    use crate::position::{BoardPosition, File::*, Rank::*};
     use crate::piece::{check_square, evaluate_vector};
 
     #[test]
@@ -2,6 +2,7 @@ use crate::position::{BoardPosition, File::*, Rank::*};
 use crate::piece::{Color, Piece};
 use crate::board::{Board, MoveType, Turn};
 use crate::board::check::is_in_check;
+use crate::piece::shorthands::*;
 
 use super::CastlingState;
 
@@ -12,7 +13,12 @@ pub fn get_king_moves(
     castling_state: &CastlingState,
     player_color: &Turn,
 ) -> Board<MoveType> {
-    let mut move_map = piece.get_standard_valid_move(board, position, position, player_color);
+    let mut move_map = piece.get_standard_moves(board, position, player_color);
+
+    // The king can not castle out of check
+    if is_in_check(board, position, player_color) {
+        return move_map;
+    }
 
     if castling_state.0 {
         get_king_side_castle(board, &mut move_map, piece);
@@ -31,6 +37,9 @@ fn get_king_side_castle(
 ) {
     match piece {
         Color::White(_) => {
+            if board.get(&BoardPosition::from((H, One))).as_ref() != Some(&WHITE_ROOK) {
+                return;
+            }
             if board.get(&BoardPosition::from((F, One))).is_some() {
                 return;
             }
@@ -48,6 +57,9 @@ fn get_king_side_castle(
             move_map.set(&BoardPosition::from((G, One)), Some(MoveType::Move))
         }
         Color::Black(_) => {
+            if board.get(&BoardPosition::from((H, Eight))).as_ref() != Some(&BLACK_ROOK) {
+                return;
+            }
             if board.get(&BoardPosition::from((F, Eight))).is_some() {
                 return;
             }
@@ -74,6 +86,9 @@ fn get_queen_side_castle(
 ) {
     match piece {
         Color::White(_) => {
+            if board.get(&BoardPosition::from((A, One))).as_ref() != Some(&WHITE_ROOK) {
+                return;
+            }
             if board.get(&BoardPosition::from((B, One))).is_some() {
                 return;
             }
@@ -84,9 +99,6 @@ fn get_queen_side_castle(
                 return;
             }
 
-            if is_in_check(board, &BoardPosition::from((B, One)), &Turn::White) {
-                return
-            }
             if is_in_check(board, &BoardPosition::from((C, One)), &Turn::White) {
                 return
             }
@@ -94,22 +106,22 @@ fn get_queen_side_castle(
                 return
             }
 
-            move_map.set(&BoardPosition::from((B, One)), Some(MoveType::Move))
+            move_map.set(&BoardPosition::from((C, One)), Some(MoveType::Move))
         }
         Color::Black(_) => {
+            if board.get(&BoardPosition::from((A, Eight))).as_ref() != Some(&BLACK_ROOK) {
+                return;
+            }
             if board.get(&BoardPosition::from((B, Eight))).is_some() {
                 return;
             }
             if board.get(&BoardPosition::from((C, Eight))).is_some() {
                 return;
             }
-            if board.get(&BoardPosition::from((B, Eight))).is_some() {
+            if board.get(&BoardPosition::from((D, Eight))).is_some() {
                 return;
             }
 
-            if is_in_check(board, &BoardPosition::from((B, Eight)), &Turn::Black) {
-                return
-            }
             if is_in_check(board, &BoardPosition::from((C, Eight)), &Turn::Black) {
                 return
             }
@@ -117,7 +129,7 @@ fn get_queen_side_castle(
                 return
             }
 
-            move_map.set(&BoardPosition::from((B, Eight)), Some(MoveType::Move))
+            move_map.set(&BoardPosition::from((C, Eight)), Some(MoveType::Move))
         }
     }
 }
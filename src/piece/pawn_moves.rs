@@ -52,7 +52,7 @@ pub fn get_pawn_moves(
                 }
             }
 
-            if let Ok(position) = position.add((1, 1)) {
+            if let Ok(position) = position.add((1, -1)) {
                 if matches!(board.get(&position), Some(Color::White(_))) {
                     move_map.set(&position, Some(MoveType::Capture));
                 }
@@ -1,6 +1,8 @@
 use std::ops::Range;
 
-use crate::piece::{Color, StepCount};
+use crate::ChessError;
+use crate::piece::{Color, Piece, StepCount};
+use crate::piece::shorthands::*;
 
 impl<T> Default for Color<T>
 where
@@ -20,3 +22,47 @@ impl From<StepCount> for Range<i8> {
         }
     }
 }
+
+/// Returns the letter used for the piece in FEN strings, upper case for white and lower case for
+/// black
+impl From<&Color<Piece>> for char {
+    fn from(value: &Color<Piece>) -> Self {
+        let char = match value.get_internal() {
+            Piece::King { .. } => 'k',
+            Piece::Queen => 'q',
+            Piece::Rook => 'r',
+            Piece::Bishop => 'b',
+            Piece::Knight => 'n',
+            Piece::Pawn { .. } => 'p',
+        };
+
+        match value {
+            Color::White(_) => char.to_ascii_uppercase(),
+            Color::Black(_) => char,
+        }
+    }
+}
+
+/// Parses the letter used for a piece in FEN strings, kings can not castle and pawns can not move
+/// two squares
+impl TryFrom<char> for Color<Piece> {
+    type Error = ChessError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'k' => Ok(BLACK_KING),
+            'q' => Ok(BLACK_QUEEN),
+            'b' => Ok(BLACK_BISHOP),
+            'n' => Ok(BLACK_KNIGHT),
+            'r' => Ok(BLACK_ROOK),
+            'p' => Ok(BLACK_PAWN),
+            'K' => Ok(WHITE_KING),
+            'Q' => Ok(WHITE_QUEEN),
+            'B' => Ok(WHITE_BISHOP),
+            'N' => Ok(WHITE_KNIGHT),
+            'R' => Ok(WHITE_ROOK),
+            'P' => Ok(WHITE_PAWN),
+            _ => Err(ChessError::IncorrectFenString),
+        }
+    }
+}
@@ -5,7 +5,7 @@ use crate::ChessError;
 pub const FILE: [File; 8] = [File::A, File::B, File::C, File::D, File::E, File::F, File::G, File::H];
 pub const RANK: [Rank; 8] = [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven, Rank::Eight];
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum File {
     A,
     B,
@@ -17,7 +17,7 @@ pub enum File {
     H,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Rank {
     One,
     Two,
@@ -30,7 +30,7 @@ pub enum Rank {
 }
 
 /// A struct representing a valid position on a chess board
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct BoardPosition {
     pub(crate) file: File,
     pub(crate) rank: Rank,
@@ -45,12 +45,24 @@ impl BoardPosition {
         &self.rank
     }
 
+    // The index of the square in a Board, starting on a8
+    pub(crate) fn index(&self) -> usize {
+        let (file, rank): (u8, u8) = self.into();
+
+        usize::from(file) + (7 - usize::from(rank)) * 8
+    }
+
+    // The position of an index in a Board
+    pub(crate) fn from_index(index: usize) -> Self {
+        Self::try_from(((index % 8) as u8, 7 - (index / 8) as u8)).expect("A board has 64 squares")
+    }
+
     /// Adds a vector to a position returning an error if the result is outside the board.
     ///
     /// # Example
     /// ```rust
-    /// use viktoe-chess::prelude::*;
-    /// BoardPosition::from((A, One)).add((3, 2)) == BoardPosition::from((D, Three));
+    /// use viktoe_chess::prelude::*;
+    /// assert_eq!(BoardPosition::from((A, One)).add((3, 2)).unwrap(), BoardPosition::from((D, Three)));
     /// ```
     pub fn add(&self, vector: (i8, i8)) -> Result<Self, ChessError> {
         let (file, rank): (u8, u8) = self.into();
@@ -106,6 +118,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn algebraic_notation() {
+        assert_eq!(BoardPosition::from((E, Four)).to_string(), "e4");
+        assert_eq!("h8".parse::<BoardPosition>().unwrap(), BoardPosition::from((H, Eight)));
+        assert!("i1".parse::<BoardPosition>().is_err());
+        assert!("a9".parse::<BoardPosition>().is_err());
+    }
+
     #[test]
     fn board_possition_iter() {
         assert_eq!(iter().into_iter().next().unwrap(), BoardPosition::from((A, Eight)));
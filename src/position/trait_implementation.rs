@@ -1,3 +1,6 @@
+use std::fmt;
+use std::str::FromStr;
+
 use crate::ChessError;
 use crate::position::{BoardPosition, File, Rank};
 
@@ -117,3 +120,28 @@ impl From<(File, Rank)> for BoardPosition {
         }
     }
 }
+
+/// Formats the position in algebraic notation, for example `e4`
+impl fmt::Display for BoardPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (file, rank): (u8, u8) = self.into();
+
+        write!(f, "{}{}", (b'a' + file) as char, rank + 1)
+    }
+}
+
+/// Parses a position in algebraic notation, for example `e4`
+impl FromStr for BoardPosition {
+    type Err = ChessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        let (file, rank) = match (chars.next(), chars.next(), chars.next()) {
+            (Some(file @ 'a'..='h'), Some(rank @ '1'..='8'), None) => (file, rank),
+            _ => return Err(ChessError::OutOfBounds),
+        };
+
+        BoardPosition::try_from((file as u8 - b'a', rank as u8 - b'1'))
+    }
+}
@@ -1,4 +1,6 @@
 pub use crate::position::{BoardPosition, File::*, Rank::*};
 pub use crate::board::{Board, MoveType};
 pub use crate::piece::{Color, Piece};
+pub use crate::chess_move::ChessMove;
 pub use crate::{ChessError, ChessGame};
+pub use crate::variant::{Antichess, Atomic, Crazyhouse, Horde, KingOfTheHill, RacingKings, Standard, ThreeCheck, Variant};
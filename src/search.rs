@@ -0,0 +1,912 @@
+mod clock;
+mod ordering;
+mod transposition;
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::board::{Board, GameState};
+use crate::chess_move::ChessMove;
+use crate::evaluation::Weights;
+use crate::piece::{Color, Piece};
+use crate::tablebase::{Tablebase, Wdl};
+use crate::ChessGame;
+use clock::TimeManager;
+use ordering::{is_losing_capture, is_tactical, MoveOrdering};
+
+pub use clock::Clock;
+pub use transposition::{Bound, Entry, TranspositionTable, DEFAULT_HASH_SIZE};
+
+/// The score of being checkmated at the root, a mate further away scores closer to zero
+pub const MATE: i32 = 30_000;
+
+/// The deepest the search goes from the root, including the quiescence search
+pub const MAX_PLY: usize = 128;
+
+const INFINITY: i32 = MATE + 1;
+
+// The score of a win found in the tablebase, below every mate found by the search
+const TABLEBASE_WIN: i32 = MATE - 2 * MAX_PLY as i32;
+
+// The number of nodes between each check of the time limit
+const TIME_CHECK_INTERVAL: u64 = 256;
+
+/// The limits of a search, the search stops when any of them is reached. Without a depth limit
+/// the search continues until [`MAX_PLY`] or until another limit is reached.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub depth: Option<u8>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<Duration>,
+    /// The clock of a timed game, the time for the move is planned from the time left on the
+    /// clock of the player to move. More time is used when the best move changes or the score
+    /// drops between iterations, and a forced move is played at once.
+    pub clock: Option<Clock>,
+}
+
+/// The score of a position from the view of the player whos turn it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Score {
+    Centipawns(i32),
+    /// The number of moves until mate, negative if the player is getting mated
+    Mate(i32),
+}
+
+impl Score {
+    pub(crate) fn from_internal(score: i32) -> Self {
+        if score > MATE - MAX_PLY as i32 {
+            Score::Mate((MATE - score + 1) / 2)
+        } else if score < -MATE + MAX_PLY as i32 {
+            Score::Mate(-(MATE + score) / 2)
+        } else {
+            Score::Centipawns(score)
+        }
+    }
+}
+
+/// Formats the score as in UCI, `cp 35` or `mate -2`
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Score::Centipawns(centipawns) => write!(f, "cp {centipawns}"),
+            Score::Mate(moves) => write!(f, "mate {moves}"),
+        }
+    }
+}
+
+/// One of the best lines found by a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub score: Score,
+    /// The moves the search expects to be played, starting with the move ranked by the line
+    pub principal_variation: Vec<ChessMove>,
+}
+
+/// The outcome of a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    /// The best move found, None if the player has no moves
+    pub best_move: Option<ChessMove>,
+    pub score: Score,
+    /// The depth of the last completed iteration
+    pub depth: u8,
+    pub nodes: u64,
+    /// The line the search expects to be played, starting with the best move
+    pub principal_variation: Vec<ChessMove>,
+    pub time: Duration,
+    /// The number of positions found in the tablebase
+    pub tablebase_hits: u64,
+    /// The best lines ranked by score, as many as set with [`Engine::set_multi_pv`]. The first
+    /// line is the principal variation.
+    pub lines: Vec<Line>,
+}
+
+/// A chess engine searching for the best move with alpha-beta search
+///
+/// ```rust
+/// use viktoe_chess::prelude::*;
+/// use viktoe_chess::search::{Engine, Limits};
+///
+/// let game = ChessGame::default();
+///
+/// let result = Engine::default().search(&game, &Limits {
+///     depth: Some(2),
+///     ..Limits::default()
+/// });
+///
+/// assert!(result.best_move.is_some());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Engine {
+    weights: Weights,
+    transposition_table: TranspositionTable,
+    stop: Arc<AtomicBool>,
+    tablebase: Option<Arc<Tablebase>>,
+    multi_pv: usize,
+    threads: usize,
+}
+
+impl Engine {
+    /// Creates an engine evaluating positions with custom weights
+    pub fn with_weights(weights: Weights) -> Self {
+        Self {
+            weights,
+            transposition_table: TranspositionTable::default(),
+            stop: Arc::new(AtomicBool::new(false)),
+            tablebase: None,
+            multi_pv: 1,
+            threads: 1,
+        }
+    }
+
+    /// Returns a flag that stops the search when it is set, for stopping a search running on
+    /// another thread. The flag is not reset by the search.
+    pub fn get_stop_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    /// Changes the size of the transposition table in megabytes, removing every entry
+    pub fn set_hash_size(&mut self, megabytes: usize) {
+        self.transposition_table.resize(megabytes);
+    }
+
+    /// Sets the endgame tablebase used to play positions with few pieces perfectly, the moves at
+    /// the root are limited to the ones keeping the best result and positions reached by a capture
+    /// or pawn move are scored from the tables
+    pub fn set_tablebase(&mut self, tablebase: Option<Arc<Tablebase>>) {
+        self.tablebase = tablebase;
+    }
+
+    /// Sets the number of best lines searched, each line after the first is searched without the
+    /// moves of the lines before it. Searching more than one line makes the search slower.
+    pub fn set_multi_pv(&mut self, lines: usize) {
+        self.multi_pv = lines.max(1);
+    }
+
+    /// Sets the number of threads searching at once, sharing the transposition table. The first
+    /// thread reports the result, the other threads fill the table with positions it is likely to
+    /// search. A search on a single thread always gives the same result.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Forgets every searched position, used between games
+    pub fn clear(&mut self) {
+        self.transposition_table.clear();
+    }
+
+    /// Returns the positions searched by the engine
+    pub fn get_transposition_table(&self) -> &TranspositionTable {
+        &self.transposition_table
+    }
+
+    /// Searches for the best move of the player whos turn it is with iterative deepening,
+    /// returning the result of the deepest completed iteration.
+    pub fn search(&mut self, game: &ChessGame, limits: &Limits) -> SearchResult {
+        self.search_with_info(game, limits, |_| {})
+    }
+
+    /// Searches like [`Engine::search`], calling `info` with the result of each completed
+    /// iteration.
+    pub fn search_with_info(
+        &mut self,
+        game: &ChessGame,
+        limits: &Limits,
+        info: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        self.transposition_table.new_search();
+
+        let time = TimeManager::new(limits, game.get_player_turn());
+
+        let shared = Shared {
+            weights: &self.weights,
+            transposition_table: &self.transposition_table,
+            stop: &self.stop,
+            tablebase: self.tablebase.as_deref(),
+            limits,
+            multi_pv: self.multi_pv,
+            maximum_time: time.get_maximum(),
+            start: Instant::now(),
+            nodes: AtomicU64::new(0),
+            tablebase_hits: AtomicU64::new(0),
+            finished: AtomicBool::new(false),
+        };
+
+        let mut result = thread::scope(|scope| {
+            for thread in 1..self.threads {
+                let shared = &shared;
+
+                // Every other helper starts one iteration deeper, so the threads search different
+                // depths at once
+                scope.spawn(move || Worker::new(shared, thread % 2).iterative_deepening(game, None, |_| {}));
+            }
+
+            let result = Worker::new(&shared, 0).iterative_deepening(game, Some(time), info);
+            shared.finished.store(true, Ordering::Relaxed);
+
+            result
+        });
+
+        // Count the nodes the helpers searched after the main thread was done
+        result.nodes = shared.nodes.load(Ordering::Relaxed);
+        result.tablebase_hits = shared.tablebase_hits.load(Ordering::Relaxed);
+
+        result
+    }
+}
+
+impl ChessGame {
+    /// Searches for the best move with the default engine, see [`Engine`]
+    pub fn search(&self, limits: &Limits) -> SearchResult {
+        Engine::default().search(self, limits)
+    }
+}
+
+// The state shared by the threads of a search
+struct Shared<'a> {
+    weights: &'a Weights,
+    transposition_table: &'a TranspositionTable,
+    stop: &'a AtomicBool,
+    tablebase: Option<&'a Tablebase>,
+    limits: &'a Limits,
+    multi_pv: usize,
+    // The time the search stops at, from the move time or the clock
+    maximum_time: Option<Duration>,
+    start: Instant,
+    nodes: AtomicU64,
+    tablebase_hits: AtomicU64,
+    // Set when the main thread is done, stopping the helpers
+    finished: AtomicBool,
+}
+
+// The state of a single thread of a search
+struct Worker<'a> {
+    shared: &'a Shared<'a>,
+    // The number of iterations skipped at the start
+    depth_offset: usize,
+    // The moves keeping the result of the tablebase, the only moves searched at the root
+    root_moves: Option<Vec<ChessMove>>,
+    // The first moves of the lines already found in the current iteration, not searched again
+    excluded_moves: Vec<ChessMove>,
+    stopped: bool,
+    ordering: MoveOrdering,
+    // The positions played before the root and from the root to the current node, used to find
+    // repetitions
+    path: Vec<Board<Color<Piece>>>,
+    previous_principal_variation: Vec<ChessMove>,
+    following_principal_variation: bool,
+}
+
+impl<'a> Worker<'a> {
+    fn new(shared: &'a Shared<'a>, depth_offset: usize) -> Self {
+        Self {
+            shared,
+            depth_offset,
+            root_moves: None,
+            excluded_moves: Vec::new(),
+            stopped: false,
+            ordering: MoveOrdering::new(),
+            path: Vec::new(),
+            previous_principal_variation: Vec::new(),
+            following_principal_variation: false,
+        }
+    }
+
+    // Searches one iteration deeper at a time, only the main thread plans its time from the clock
+    fn iterative_deepening(
+        &mut self,
+        game: &ChessGame,
+        mut time: Option<TimeManager>,
+        mut info: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        self.root_moves = self.shared.tablebase.and_then(|tablebase| tablebase.get_best_moves(game));
+
+        if self.root_moves.is_some() {
+            self.shared.tablebase_hits.fetch_add(1, Ordering::Relaxed);
+        }
+
+        // Only the positions since the last capture or pawn move can be repeated
+        let played = game.previous_positions.len().saturating_sub(usize::from(game.half_move));
+        self.path = game.previous_positions[played..]
+            .iter()
+            .map(|position| position.board.clone())
+            .collect();
+
+        let moves = self.ordering.order(game, self.get_moves(game, 0), 0, None);
+
+        let mut result = SearchResult {
+            best_move: moves.first().cloned(),
+            score: Score::Centipawns(0),
+            depth: 0,
+            nodes: 0,
+            principal_variation: moves.first().cloned().into_iter().collect(),
+            time: Duration::ZERO,
+            tablebase_hits: 0,
+            lines: moves
+                .first()
+                .map(|chess_move| Line {
+                    score: Score::Centipawns(0),
+                    principal_variation: vec![chess_move.clone()],
+                })
+                .into_iter()
+                .collect(),
+        };
+
+        let max_depth = self.shared.limits.depth.map_or(MAX_PLY, usize::from).min(MAX_PLY);
+        let line_count = self.shared.multi_pv.clamp(1, moves.len().max(1));
+        let mut previous_lines: Vec<Vec<ChessMove>> = Vec::new();
+
+        for depth in (1 + self.depth_offset).min(max_depth)..=max_depth {
+            let mut lines: Vec<(i32, Vec<ChessMove>)> = Vec::new();
+
+            self.excluded_moves.clear();
+
+            for index in 0..line_count {
+                let mut principal_variation = Vec::new();
+
+                self.previous_principal_variation = previous_lines.get(index).cloned().unwrap_or_default();
+                self.following_principal_variation = true;
+                let score = self.negamax(game, depth as i32, 0, -INFINITY, INFINITY, &mut principal_variation);
+
+                if self.stopped {
+                    break;
+                }
+
+                self.extend_principal_variation(game, &mut principal_variation, depth);
+                self.excluded_moves.extend(principal_variation.first().cloned());
+
+                lines.push((score, principal_variation));
+            }
+
+            // An unfinished iteration is thrown away, its later lines have not been searched
+            if self.stopped {
+                break;
+            }
+
+            // A line found later can score higher when the search is unstable
+            lines.sort_by_key(|(score, _)| -score);
+
+            let (score, principal_variation) = (lines[0].0, &lines[0].1);
+
+            result.best_move = principal_variation.first().cloned();
+            result.score = Score::from_internal(score);
+            result.depth = depth as u8;
+            result.principal_variation = principal_variation.clone();
+            result.lines = lines
+                .iter()
+                .filter(|(_, principal_variation)| !principal_variation.is_empty())
+                .map(|(score, principal_variation)| Line {
+                    score: Score::from_internal(*score),
+                    principal_variation: principal_variation.clone(),
+                })
+                .collect();
+
+            // A mate that has been found within the depth can not get any shorter
+            let is_mate = lines.iter().all(|(score, _)| score.abs() >= MATE - depth as i32);
+            previous_lines = lines.into_iter().map(|(_, principal_variation)| principal_variation).collect();
+
+            result.nodes = self.shared.nodes.load(Ordering::Relaxed);
+            result.time = self.shared.start.elapsed();
+            result.tablebase_hits = self.shared.tablebase_hits.load(Ordering::Relaxed);
+            info(&result);
+
+            if is_mate {
+                break;
+            }
+
+            let elapsed = self.shared.start.elapsed();
+            let best_move = result.best_move.as_ref();
+
+            if time.as_mut().is_some_and(|time| !time.next_iteration(elapsed, best_move, score, moves.len())) {
+                break;
+            }
+        }
+
+        result.nodes = self.shared.nodes.load(Ordering::Relaxed);
+        result.time = self.shared.start.elapsed();
+        result.tablebase_hits = self.shared.tablebase_hits.load(Ordering::Relaxed);
+
+        result
+    }
+
+    // Returns the legal moves, at the root only the ones keeping the result of the tablebase and
+    // not starting an earlier line
+    fn get_moves(&self, game: &ChessGame, ply: usize) -> Vec<ChessMove> {
+        let mut moves = match &self.root_moves {
+            Some(root_moves) if ply == 0 => root_moves.clone(),
+            _ => game.get_legal_moves(),
+        };
+
+        if ply == 0 {
+            moves.retain(|chess_move| !self.excluded_moves.contains(chess_move));
+        }
+
+        moves
+    }
+
+    fn negamax(
+        &mut self,
+        game: &ChessGame,
+        depth: i32,
+        ply: usize,
+        mut alpha: i32,
+        mut beta: i32,
+        principal_variation: &mut Vec<ChessMove>,
+    ) -> i32 {
+        principal_variation.clear();
+
+        if let Some(score) = self.terminal_score(game, ply) {
+            return score;
+        }
+
+        if ply > 0 && self.is_repetition(game) {
+            return 0;
+        }
+
+        // Tables are only probed after captures and pawn moves, as every other move keeps the
+        // same material
+        if ply > 0 && game.half_move == 0 {
+            if let Some(wdl) = self.shared.tablebase.and_then(|tablebase| tablebase.probe_wdl(game)) {
+                self.shared.tablebase_hits.fetch_add(1, Ordering::Relaxed);
+
+                return match wdl {
+                    Wdl::Win => TABLEBASE_WIN - ply as i32,
+                    Wdl::Loss => -TABLEBASE_WIN + ply as i32,
+                    // The fifty move rule makes the other results draws
+                    _ => 0,
+                };
+            }
+        }
+
+        if depth <= 0 || ply >= MAX_PLY {
+            return self.quiescence(game, ply, alpha, beta, 0);
+        }
+
+        if self.count_node() {
+            self.stopped = true;
+            return 0;
+        }
+
+        // A shorter mate has already been found
+        alpha = alpha.max(-MATE + ply as i32);
+        beta = beta.min(MATE - ply as i32 - 1);
+        if alpha >= beta {
+            return alpha;
+        }
+
+        let key = game.get_hash();
+        let mut table_move = None;
+
+        if let Some(entry) = self.shared.transposition_table.probe(key) {
+            table_move = entry.best_move.clone();
+
+            // The root is always searched to find the principal variation
+            if ply > 0 && i32::from(entry.depth) >= depth {
+                let score = entry.get_score(ply);
+
+                match entry.bound {
+                    Bound::Exact => return score,
+                    Bound::Lower if score >= beta => return score,
+                    Bound::Upper if score <= alpha => return score,
+                    _ => {}
+                }
+            }
+        }
+
+        let moves = self.get_moves(game, ply);
+
+        if moves.is_empty() {
+            return self.no_moves_score(game, ply);
+        }
+
+        let first_move = match self.previous_principal_variation.get(ply) {
+            Some(chess_move) if self.following_principal_variation => Some(chess_move.clone()),
+            _ => {
+                self.following_principal_variation = false;
+                table_move
+            }
+        };
+        let moves = self.ordering.order(game, moves, ply, first_move.as_ref());
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        let mut child_principal_variation = Vec::new();
+
+        for (index, chess_move) in moves.into_iter().enumerate() {
+            if index > 0 {
+                self.following_principal_variation = false;
+            }
+
+            let mut child = game.clone_position();
+            child.play_unchecked(&chess_move);
+
+            self.path.push(game.board.clone());
+            let score = -self.negamax(&child, depth - 1, ply + 1, -beta, -alpha, &mut child_principal_variation);
+            self.path.pop();
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(chess_move.clone());
+            }
+
+            if score > alpha {
+                alpha = score;
+
+                principal_variation.clear();
+                principal_variation.push(chess_move.clone());
+                principal_variation.append(&mut child_principal_variation);
+            }
+
+            if alpha >= beta {
+                if !is_tactical(game, &chess_move) {
+                    self.ordering.update(&chess_move, depth, ply);
+                }
+
+                break;
+            }
+        }
+
+        let bound = if best_score >= beta {
+            Bound::Lower
+        } else if best_score > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+
+        // The score of the root without some of its moves is not the score of the position
+        if ply > 0 || self.excluded_moves.is_empty() {
+            self.shared.transposition_table
+                .store(key, depth as u8, bound, best_score, best_move, ply);
+        }
+
+        best_score
+    }
+
+    // Searches captures, promotions and checks until the position is quiet, quiet checks are only
+    // searched directly after the main search
+    fn quiescence(&mut self, game: &ChessGame, ply: usize, mut alpha: i32, beta: i32, quiescence_ply: usize) -> i32 {
+        if self.count_node() {
+            self.stopped = true;
+            return 0;
+        }
+
+        if let Some(score) = self.terminal_score(game, ply) {
+            return score;
+        }
+
+        let turn = game.get_player_turn();
+        let in_check = game.is_in_check(turn);
+
+        if ply >= MAX_PLY {
+            return self.shared.weights.evaluate(game).relative(turn);
+        }
+
+        let mut best_score = -INFINITY;
+
+        if !in_check {
+            best_score = self.shared.weights.evaluate(game).relative(turn);
+
+            if best_score >= beta {
+                return best_score;
+            }
+
+            alpha = alpha.max(best_score);
+        }
+
+        let moves = game.get_legal_moves();
+
+        if moves.is_empty() {
+            return self.no_moves_score(game, ply);
+        }
+
+        let has_standard_captures = game.get_variant().has_standard_captures();
+
+        for chess_move in self.ordering.order(game, moves, ply, None) {
+            let is_tactical = is_tactical(game, &chess_move);
+
+            if !in_check && !is_tactical && quiescence_ply > 0 {
+                continue;
+            }
+
+            // Captures losing material in the exchange can not raise the score of a quiet
+            // position, except in variants where captures follow other rules
+            if !in_check && has_standard_captures && is_losing_capture(game, &chess_move) {
+                continue;
+            }
+
+            let mut child = game.clone_position();
+            child.play_unchecked(&chess_move);
+
+            // Quiet moves are only searched to get out of or to give check
+            if !in_check && !is_tactical && !child.is_in_check(child.get_player_turn()) {
+                continue;
+            }
+
+            let score = -self.quiescence(&child, ply + 1, -beta, -alpha, quiescence_ply + 1);
+
+            if self.stopped {
+                return 0;
+            }
+
+            best_score = best_score.max(score);
+            alpha = alpha.max(score);
+
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best_score
+    }
+
+    // Cutoffs from the transposition table end the principal variation early, it is continued
+    // with the best moves stored in the table
+    fn extend_principal_variation(&self, game: &ChessGame, principal_variation: &mut Vec<ChessMove>, depth: usize) {
+        let mut position = game.clone_position();
+
+        for chess_move in principal_variation.iter() {
+            position.play_unchecked(chess_move);
+        }
+
+        while principal_variation.len() < depth {
+            let chess_move = match self.shared.transposition_table.probe(position.get_hash()) {
+                Some(Entry {
+                    best_move: Some(chess_move),
+                    ..
+                }) => chess_move.clone(),
+                _ => break,
+            };
+
+            if !position.get_legal_moves().contains(&chess_move) {
+                break;
+            }
+
+            position.play_unchecked(&chess_move);
+            principal_variation.push(chess_move);
+        }
+    }
+
+    // Returns the score of a game that has ended
+    fn terminal_score(&self, game: &ChessGame, ply: usize) -> Option<i32> {
+        match game.get_game_state() {
+            GameState::CheckMate => Some(-MATE + ply as i32),
+            GameState::Draw(_) => Some(0),
+            GameState::Win(winner, _) if winner == game.get_player_turn() => Some(MATE - ply as i32),
+            GameState::Win(..) => Some(-MATE + ply as i32),
+            _ => None,
+        }
+    }
+
+    // A player without moves is checkmated if they are in check and stalemated if they are not
+    fn no_moves_score(&self, game: &ChessGame, ply: usize) -> i32 {
+        if game.is_in_check(game.get_player_turn()) {
+            -MATE + ply as i32
+        } else {
+            0
+        }
+    }
+
+    // Positions can only repeat with the same player to move, every other ply
+    fn is_repetition(&self, game: &ChessGame) -> bool {
+        self.path
+            .iter()
+            .rev()
+            .skip(1)
+            .step_by(2)
+            .any(|board| *board == game.board)
+    }
+
+    // Counts a searched node, returning whether the search should stop
+    fn count_node(&self) -> bool {
+        let shared = self.shared;
+        let nodes = shared.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+
+        if shared.stop.load(Ordering::Relaxed) || shared.finished.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        if shared.limits.nodes.is_some_and(|limit| nodes >= limit) {
+            return true;
+        }
+
+        match shared.maximum_time {
+            Some(maximum) if nodes.is_multiple_of(TIME_CHECK_INTERVAL) => shared.start.elapsed() >= maximum,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth(depth: u8) -> Limits {
+        Limits {
+            depth: Some(depth),
+            ..Limits::default()
+        }
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        let game = ChessGame::from_fen(String::from(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+        ))
+        .unwrap();
+
+        let result = game.search(&depth(2));
+
+        assert_eq!(result.best_move, Some("h5f7".parse().unwrap()));
+        assert_eq!(result.score, Score::Mate(1));
+        assert_eq!(result.depth, 1);
+    }
+
+    #[test]
+    fn captures_hanging_queen() {
+        let game = ChessGame::from_fen(String::from("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1")).unwrap();
+
+        let result = game.search(&depth(2));
+
+        assert_eq!(result.best_move, Some("d1d5".parse().unwrap()));
+        assert!(matches!(result.score, Score::Centipawns(score) if score > 300));
+    }
+
+    #[test]
+    fn principal_variation_is_playable() {
+        let game = ChessGame::from_fen(String::from("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1")).unwrap();
+
+        let result = game.search(&depth(3));
+        let mut line = game.clone();
+
+        assert_eq!(result.principal_variation.first(), result.best_move.as_ref());
+
+        for chess_move in &result.principal_variation {
+            line.make_move(chess_move).unwrap();
+        }
+    }
+
+    #[test]
+    fn repeats_positions_played_before_the_search() {
+        let mut game = ChessGame::from_fen(String::from("k7/8/8/8/8/8/8/K3Q3 w - - 0 1")).unwrap();
+
+        for chess_move in ["e1e2", "a8a7", "e2e1"] {
+            game.make_move(&chess_move.parse().unwrap()).unwrap();
+        }
+
+        // Every other move keeps black a queen down, going back to a8 repeats the start
+        let result = game.search(&depth(3));
+
+        assert_eq!(result.best_move, Some("a7a8".parse().unwrap()));
+        assert_eq!(result.score, Score::Centipawns(0));
+    }
+
+    #[test]
+    fn transposition_table_is_reused() {
+        let game = ChessGame::from_fen(String::from(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+        ))
+        .unwrap();
+        let mut engine = Engine::default();
+
+        let first = engine.search(&game, &depth(3));
+        let second = engine.search(&game, &depth(3));
+
+        assert!(second.nodes < first.nodes);
+        assert_eq!(second.best_move, first.best_move);
+        assert!(engine.get_transposition_table().probe(game.get_hash()).is_some());
+
+        engine.clear();
+
+        assert!(engine.get_transposition_table().probe(game.get_hash()).is_none());
+    }
+
+    #[test]
+    fn searches_several_lines() {
+        let game = ChessGame::from_fen(String::from(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+        ))
+        .unwrap();
+        let mut engine = Engine::default();
+        engine.set_multi_pv(3);
+
+        let result = engine.search(&game, &depth(3));
+
+        assert_eq!(result.lines.len(), 3);
+        assert_eq!(result.lines[0].principal_variation, result.principal_variation);
+        assert_eq!(result.lines[0].score, Score::Mate(1));
+        assert_eq!(result.depth, 3);
+
+        let first_moves: Vec<_> = result.lines.iter().map(|line| &line.principal_variation[0]).collect();
+
+        assert!(!first_moves[1..].contains(&first_moves[0]));
+        assert_ne!(first_moves[1], first_moves[2]);
+        assert!(matches!(result.lines[1].score, Score::Centipawns(_)));
+
+        // There are only three legal moves
+        let game = ChessGame::from_fen(String::from("k7/8/8/8/8/8/8/K6R b - - 0 1")).unwrap();
+        engine.set_multi_pv(5);
+
+        assert_eq!(engine.search(&game, &depth(2)).lines.len(), 3);
+    }
+
+    #[test]
+    fn searches_on_several_threads() {
+        fn is_shareable<T: Send + Sync>() {}
+        is_shareable::<ChessGame>();
+        is_shareable::<Engine>();
+
+        let game = ChessGame::from_fen(String::from(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+        ))
+        .unwrap();
+        let mut engine = Engine::default();
+        engine.set_threads(4);
+
+        let result = engine.search(&game, &depth(4));
+
+        assert_eq!(result.best_move, Some("h5f7".parse().unwrap()));
+        assert_eq!(result.score, Score::Mate(1));
+
+        // A single thread searches the same nodes every time
+        let first = Engine::default().search(&game, &depth(3));
+        let second = Engine::default().search(&game, &depth(3));
+
+        assert_eq!(first.nodes, second.nodes);
+        assert_eq!(first.principal_variation, second.principal_variation);
+    }
+
+    #[test]
+    fn forced_moves_are_played_at_once() {
+        let game = ChessGame::from_fen(String::from("k7/8/1K6/8/8/8/8/7R b - - 0 1")).unwrap();
+        let limits = Limits {
+            clock: Some(Clock::new(Duration::from_secs(60), Duration::ZERO)),
+            ..Limits::default()
+        };
+
+        let result = game.search(&limits);
+
+        assert_eq!(result.best_move, Some("a8b8".parse().unwrap()));
+        assert_eq!(result.depth, 1);
+    }
+
+    #[test]
+    fn stalemate_has_no_move() {
+        let game = ChessGame::from_fen(String::from("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1")).unwrap();
+
+        let result = game.search(&depth(3));
+
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, Score::Centipawns(0));
+    }
+
+    #[test]
+    fn node_limit_is_respected() {
+        let limits = Limits {
+            nodes: Some(500),
+            ..Limits::default()
+        };
+
+        let result = ChessGame::default().search(&limits);
+
+        assert!(result.nodes <= 500);
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn mate_scores() {
+        assert_eq!(Score::from_internal(MATE - 1), Score::Mate(1));
+        assert_eq!(Score::from_internal(MATE - 3), Score::Mate(2));
+        assert_eq!(Score::from_internal(-MATE + 2), Score::Mate(-1));
+        assert_eq!(Score::from_internal(150), Score::Centipawns(150));
+        assert_eq!(Score::Mate(-1).to_string(), "mate -1");
+    }
+}
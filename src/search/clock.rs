@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use crate::board::Turn;
+use crate::chess_move::ChessMove;
+use crate::search::Limits;
+
+// Time kept back from the clock for the delay of the GUI
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+
+// The number of moves the remaining time is divided over when the number is not known
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+// How many times the planned time a single move may use at most
+const MAXIMUM_FACTOR: u32 = 4;
+
+// A drop of the score between two iterations in centipawns that counts as a fail low
+const FAIL_LOW_MARGIN: i32 = 30;
+
+/// The time left on the clocks of both players in a timed game
+///
+/// ```rust
+/// use std::time::Duration;
+/// use viktoe_chess::board::Turn;
+/// use viktoe_chess::search::Clock;
+///
+/// let mut clock = Clock::new(Duration::from_secs(60), Duration::from_secs(1));
+///
+/// clock.spend(&Turn::White, Duration::from_secs(5));
+///
+/// assert_eq!(clock.get_time(&Turn::White), Duration::from_secs(56));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Clock {
+    pub white_time: Duration,
+    pub black_time: Duration,
+    pub white_increment: Duration,
+    pub black_increment: Duration,
+    /// The number of moves until the next time control, None if the rest of the game is played
+    /// on the remaining time
+    pub moves_to_go: Option<u32>,
+}
+
+impl Clock {
+    /// Creates a clock giving both players the same time and increment
+    pub fn new(time: Duration, increment: Duration) -> Self {
+        Self {
+            white_time: time,
+            black_time: time,
+            white_increment: increment,
+            black_increment: increment,
+            moves_to_go: None,
+        }
+    }
+
+    /// Returns the time left for a player
+    pub fn get_time(&self, turn: &Turn) -> Duration {
+        match turn {
+            Turn::White => self.white_time,
+            Turn::Black => self.black_time,
+        }
+    }
+
+    /// Returns the time a player gets after each move
+    pub fn get_increment(&self, turn: &Turn) -> Duration {
+        match turn {
+            Turn::White => self.white_increment,
+            Turn::Black => self.black_increment,
+        }
+    }
+
+    /// Takes the time a player used for a move from their clock and adds the increment
+    pub fn spend(&mut self, turn: &Turn, elapsed: Duration) {
+        let increment = self.get_increment(turn);
+
+        let time = match turn {
+            Turn::White => &mut self.white_time,
+            Turn::Black => &mut self.black_time,
+        };
+
+        *time = time.saturating_sub(elapsed) + increment;
+    }
+}
+
+// Plans the time of a search, a new iteration is only started if it is likely to finish within
+// the planned time. The planned time grows while the best move keeps changing or the score drops.
+pub(crate) struct TimeManager {
+    // The time planned for the move, None without a clock
+    optimum: Option<Duration>,
+    // The time the search is stopped at even within an iteration
+    maximum: Option<Duration>,
+    best_move: Option<ChessMove>,
+    score: i32,
+    // The extra time in percent of the planned time, halved every iteration the best move stays
+    instability: u32,
+}
+
+impl TimeManager {
+    pub(crate) fn new(limits: &Limits, turn: &Turn) -> Self {
+        let (optimum, maximum) = match &limits.clock {
+            Some(clock) => {
+                let time = clock.get_time(turn);
+                let moves_to_go = clock.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+                let available = time.saturating_sub(MOVE_OVERHEAD);
+
+                let optimum = (time / moves_to_go + clock.get_increment(turn) * 3 / 4)
+                    .min(available)
+                    .max(Duration::from_millis(1));
+                let maximum = (optimum * MAXIMUM_FACTOR).min(available / 3).max(optimum);
+
+                (Some(optimum), Some(maximum))
+            }
+            None => (None, None),
+        };
+
+        Self {
+            optimum,
+            maximum: match (maximum, limits.movetime) {
+                (Some(maximum), Some(movetime)) => Some(maximum.min(movetime)),
+                (maximum, movetime) => maximum.or(movetime),
+            },
+            best_move: None,
+            score: 0,
+            instability: 0,
+        }
+    }
+
+    pub(crate) fn get_maximum(&self) -> Option<Duration> {
+        self.maximum
+    }
+
+    // Returns whether there is time for another iteration after one finished with a best move and
+    // score, a forced move is played after the first iteration
+    pub(crate) fn next_iteration(
+        &mut self,
+        elapsed: Duration,
+        best_move: Option<&ChessMove>,
+        score: i32,
+        move_count: usize,
+    ) -> bool {
+        let optimum = match self.optimum {
+            Some(optimum) => optimum,
+            None => return true,
+        };
+
+        let first_iteration = self.best_move.is_none();
+
+        self.instability /= 2;
+
+        if !first_iteration && self.best_move.as_ref() != best_move {
+            self.instability += 100;
+        }
+
+        let mut scale = 100 + self.instability;
+
+        if !first_iteration && score < self.score - FAIL_LOW_MARGIN {
+            scale += 50;
+        }
+
+        self.best_move = best_move.cloned();
+        self.score = score;
+
+        if move_count <= 1 {
+            return false;
+        }
+
+        // The next iteration takes about as long as every iteration before it
+        elapsed * 2 * 100 < optimum * scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(time: u64, increment: u64, moves_to_go: Option<u32>) -> Limits {
+        Limits {
+            clock: Some(Clock {
+                moves_to_go,
+                ..Clock::new(Duration::from_millis(time), Duration::from_millis(increment))
+            }),
+            ..Limits::default()
+        }
+    }
+
+    #[test]
+    fn allocates_time_from_the_clock() {
+        let time = TimeManager::new(&clock(60_000, 1000, Some(20)), &Turn::White);
+
+        assert_eq!(time.optimum, Some(Duration::from_millis(3750)));
+        assert_eq!(time.get_maximum(), Some(Duration::from_millis(15_000)));
+
+        let time = TimeManager::new(&clock(100, 0, Some(20)), &Turn::Black);
+
+        assert_eq!(time.optimum, Some(Duration::from_millis(5)));
+        assert_eq!(time.get_maximum(), Some(Duration::from_millis(50) / 3));
+
+        let time = TimeManager::new(&clock(30_000, 0, None), &Turn::White);
+
+        assert_eq!(time.optimum, Some(Duration::from_secs(1)));
+
+        let limits = Limits {
+            movetime: Some(Duration::from_secs(2)),
+            ..clock(60_000, 0, None)
+        };
+
+        assert_eq!(TimeManager::new(&limits, &Turn::White).get_maximum(), Some(Duration::from_secs(2)));
+        assert_eq!(TimeManager::new(&Limits::default(), &Turn::White).get_maximum(), None);
+    }
+
+    #[test]
+    fn extends_time_on_unstable_positions() {
+        let first: ChessMove = "e2e4".parse().unwrap();
+        let second: ChessMove = "d2d4".parse().unwrap();
+        let elapsed = Duration::from_millis(1200);
+
+        // Half of the planned two seconds has been used, another iteration does not fit
+        let mut time = TimeManager::new(&clock(60_000, 0, None), &Turn::White);
+
+        assert!(time.next_iteration(Duration::from_millis(10), Some(&first), 20, 20));
+        assert!(!time.next_iteration(elapsed, Some(&first), 20, 20));
+
+        // A new best move
+        let mut time = TimeManager::new(&clock(60_000, 0, None), &Turn::White);
+
+        assert!(time.next_iteration(Duration::from_millis(10), Some(&first), 20, 20));
+        assert!(time.next_iteration(elapsed, Some(&second), 20, 20));
+
+        // A dropping score
+        let mut time = TimeManager::new(&clock(60_000, 0, None), &Turn::White);
+
+        assert!(time.next_iteration(Duration::from_millis(10), Some(&first), 20, 20));
+        assert!(time.next_iteration(elapsed, Some(&first), -50, 20));
+
+        // A single legal move
+        let mut time = TimeManager::new(&clock(60_000, 0, None), &Turn::White);
+
+        assert!(!time.next_iteration(Duration::from_millis(10), Some(&first), 20, 1));
+
+        // Without a clock the search continues until another limit is reached
+        let mut time = TimeManager::new(&Limits::default(), &Turn::White);
+
+        assert!(time.next_iteration(Duration::from_secs(60), Some(&first), 20, 1));
+    }
+}
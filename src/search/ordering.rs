@@ -0,0 +1,162 @@
+use crate::chess_move::ChessMove;
+use crate::piece::Piece;
+use crate::search::MAX_PLY;
+use crate::ChessGame;
+
+// Scores used to sort the moves, the best move from an earlier search is always tried first
+const BEST_MOVE: i32 = 1_000_000;
+const CAPTURE: i32 = 100_000;
+const KILLER: i32 = 90_000;
+const LOSING_CAPTURE: i32 = -100_000;
+
+// Keeps track of the quiet moves that caused cutoffs earlier in the search
+pub(crate) struct MoveOrdering {
+    killers: Vec<[Option<ChessMove>; 2]>,
+    history: Vec<i32>,
+}
+
+impl MoveOrdering {
+    pub(crate) fn new() -> Self {
+        Self {
+            killers: vec![[None, None]; MAX_PLY + 1],
+            history: vec![0; 64 * 64],
+        }
+    }
+
+    // Sorts the moves with the best move first, then captures by most valuable victim and least
+    // valuable attacker, then killer moves, then quiet moves by their history and last the
+    // captures losing material in the exchange
+    pub(crate) fn order(
+        &self,
+        game: &ChessGame,
+        moves: Vec<ChessMove>,
+        ply: usize,
+        best_move: Option<&ChessMove>,
+    ) -> Vec<ChessMove> {
+        let mut scored: Vec<(i32, ChessMove)> = moves
+            .into_iter()
+            .map(|chess_move| {
+                let score = if Some(&chess_move) == best_move {
+                    BEST_MOVE
+                } else if let Some(score) = mvv_lva(game, &chess_move) {
+                    if game.static_exchange(&chess_move) < 0 {
+                        LOSING_CAPTURE + score
+                    } else {
+                        CAPTURE + score
+                    }
+                } else if self.killers[ply].contains(&Some(chess_move.clone())) {
+                    KILLER
+                } else {
+                    history_index(&chess_move).map_or(0, |index| self.history[index])
+                };
+
+                (score, chess_move)
+            })
+            .collect();
+
+        // A stable sort keeps the order of the move generator between equal moves
+        scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        scored.into_iter().map(|(_, chess_move)| chess_move).collect()
+    }
+
+    // Remembers a quiet move that caused a cutoff
+    pub(crate) fn update(&mut self, chess_move: &ChessMove, depth: i32, ply: usize) {
+        let killers = &mut self.killers[ply];
+
+        if killers[0].as_ref() != Some(chess_move) {
+            killers[1] = killers[0].take();
+            killers[0] = Some(chess_move.clone());
+        }
+
+        if let Some(index) = history_index(chess_move) {
+            self.history[index] = (self.history[index] + depth * depth).min(KILLER - 1);
+        }
+    }
+}
+
+/// Returns true if the move captures a piece or promotes a pawn
+pub(crate) fn is_tactical(game: &ChessGame, chess_move: &ChessMove) -> bool {
+    matches!(chess_move, ChessMove::Move { promotion: Some(_), .. }) || mvv_lva(game, chess_move).is_some()
+}
+
+// Returns true if the move captures without promoting and loses material in the exchange
+pub(crate) fn is_losing_capture(game: &ChessGame, chess_move: &ChessMove) -> bool {
+    matches!(chess_move, ChessMove::Move { promotion: None, .. })
+        && mvv_lva(game, chess_move).is_some()
+        && game.static_exchange(chess_move) < 0
+}
+
+// Returns the score of a capture by most valuable victim and least valuable attacker, or None if
+// the move does not capture
+pub(crate) fn mvv_lva(game: &ChessGame, chess_move: &ChessMove) -> Option<i32> {
+    let (from, to) = match chess_move {
+        ChessMove::Move { from, to, .. } => (from, to),
+        ChessMove::Drop { .. } => return None,
+    };
+
+    let attacker = game.get_square(from).as_ref()?;
+
+    let victim = match game.get_square(to) {
+        Some(victim) if !victim.same_color(game.get_player_turn()) => victim.get_internal(),
+        // A pawn moving diagonally to an empty square captures by en passant
+        None if matches!(attacker.get_internal(), Piece::Pawn { .. }) && from.get_file() != to.get_file() => {
+            attacker.get_internal()
+        }
+        _ => return None,
+    };
+
+    Some(value(victim) * 10 - value(attacker.get_internal()))
+}
+
+pub(crate) fn value(piece: &Piece) -> i32 {
+    match piece {
+        Piece::Pawn { .. } => 1,
+        Piece::Knight | Piece::Bishop => 3,
+        Piece::Rook => 5,
+        Piece::Queen => 9,
+        Piece::King { .. } => 10,
+    }
+}
+
+fn history_index(chess_move: &ChessMove) -> Option<usize> {
+    match chess_move {
+        ChessMove::Move { from, to, .. } => Some(from.index() * 64 + to.index()),
+        ChessMove::Drop { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_are_ordered_by_victim_and_attacker() {
+        let game = ChessGame::from_fen(String::from("4k3/8/8/3q4/2P5/8/8/3RK3 w - - 0 1")).unwrap();
+        let moves = MoveOrdering::new().order(&game, game.get_legal_moves(), 0, None);
+
+        assert_eq!(moves[0].to_string(), "c4d5");
+        assert_eq!(moves[1].to_string(), "d1d5");
+    }
+
+    #[test]
+    fn losing_captures_are_tried_last() {
+        let game = ChessGame::from_fen(String::from("4k3/8/4p3/3p4/8/8/3Q4/4K3 w - - 0 1")).unwrap();
+        let moves = MoveOrdering::new().order(&game, game.get_legal_moves(), 0, None);
+
+        assert_eq!(moves.last().unwrap().to_string(), "d2d5");
+    }
+
+    #[test]
+    fn killers_are_tried_before_quiet_moves() {
+        let game = ChessGame::default();
+        let mut ordering = MoveOrdering::new();
+
+        ordering.update(&"g1f3".parse().unwrap(), 3, 1);
+
+        let moves = ordering.order(&game, game.get_legal_moves(), 1, Some(&"e2e4".parse().unwrap()));
+
+        assert_eq!(moves[0].to_string(), "e2e4");
+        assert_eq!(moves[1].to_string(), "g1f3");
+    }
+}
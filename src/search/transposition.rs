@@ -0,0 +1,355 @@
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::chess_move::ChessMove;
+use crate::piece::{PawnState, Piece};
+use crate::position::BoardPosition;
+use crate::search::{MATE, MAX_PLY};
+
+/// The size of the transposition table of a new engine in megabytes
+pub const DEFAULT_HASH_SIZE: usize = 16;
+
+// Set in the data of every stored entry, an empty slot is all zeros
+const VALID: u64 = 1 << 63;
+
+/// How the score of an entry relates to the real score of the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is the real score
+    Exact,
+    /// The real score is at least the score, the search was cut off
+    Lower,
+    /// The real score is at most the score, no move raised alpha
+    Upper,
+}
+
+/// A position stored in the transposition table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    key: u64,
+    pub depth: u8,
+    pub bound: Bound,
+    score: i32,
+    pub best_move: Option<ChessMove>,
+    age: u8,
+}
+
+impl Entry {
+    /// Returns the score of the entry, with mates counted from the ply the entry is used at
+    pub fn get_score(&self, ply: usize) -> i32 {
+        score_from_table(self.score, ply)
+    }
+
+    // Packs everything but the key into 64 bits, the score is at most a mate and fits in 16 bits
+    fn pack(&self) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 0,
+            Bound::Lower => 1,
+            Bound::Upper => 2,
+        };
+
+        VALID
+            | u64::from(self.score.clamp(i16::MIN.into(), i16::MAX.into()) as i16 as u16)
+            | u64::from(self.depth) << 16
+            | bound << 24
+            | u64::from(self.age) << 26
+            | self.best_move.as_ref().map_or(0, pack_move) << 34
+    }
+
+    fn unpack(key: u64, data: u64) -> Option<Self> {
+        if data & VALID == 0 {
+            return None;
+        }
+
+        Some(Self {
+            key,
+            depth: (data >> 16) as u8,
+            bound: match (data >> 24) & 3 {
+                0 => Bound::Exact,
+                1 => Bound::Lower,
+                _ => Bound::Upper,
+            },
+            score: i32::from(data as u16 as i16),
+            best_move: unpack_move(data >> 34),
+            age: (data >> 26) as u8,
+        })
+    }
+}
+
+// An entry is stored as its data and the key xored with the data, so an entry written by two
+// threads at once is found to be broken instead of being read with the wrong key
+#[derive(Debug, Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+/// A fixed size table of searched positions keyed by their hash, see [`ChessGame::get_hash`].
+///
+/// An entry is only replaced by a search of the same depth or deeper, unless the entry is left
+/// from an earlier search. The table can be shared by several threads searching at once without
+/// locking.
+///
+/// [`ChessGame::get_hash`]: crate::ChessGame::get_hash
+#[derive(Debug)]
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+    age: u8,
+}
+
+impl TranspositionTable {
+    /// Creates a table using about `megabytes` of memory
+    pub fn new(megabytes: usize) -> Self {
+        let length = (megabytes * 1024 * 1024 / mem::size_of::<Slot>()).max(1);
+
+        Self {
+            slots: (0..length).map(|_| Slot::default()).collect(),
+            age: 0,
+        }
+    }
+
+    /// Changes the size of the table, removing every entry
+    pub fn resize(&mut self, megabytes: usize) {
+        *self = Self::new(megabytes);
+    }
+
+    /// Removes every entry, used between games
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = Slot::default());
+        self.age = 0;
+    }
+
+    /// Marks the entries as left from an earlier search, so they can be replaced
+    pub fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    /// Returns the entry of a position if it is in the table
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.load(self.index(key)).filter(|entry| entry.key == key)
+    }
+
+    /// Stores the result of searching a position `ply` moves from the root
+    pub fn store(
+        &self,
+        key: u64,
+        depth: u8,
+        bound: Bound,
+        score: i32,
+        best_move: Option<ChessMove>,
+        ply: usize,
+    ) {
+        let index = self.index(key);
+        let previous = self.load(index);
+
+        let replace = match &previous {
+            Some(entry) => entry.age != self.age || depth >= entry.depth,
+            None => true,
+        };
+
+        if replace {
+            // Keep the best move of the position if the new search did not find one
+            let best_move = match (previous, best_move) {
+                (Some(entry), None) if entry.key == key => entry.best_move,
+                (_, best_move) => best_move,
+            };
+
+            let data = Entry {
+                key,
+                depth,
+                bound,
+                score: score_to_table(score, ply),
+                best_move,
+                age: self.age,
+            }
+            .pack();
+
+            let slot = &self.slots[index];
+            slot.key.store(key ^ data, Ordering::Relaxed);
+            slot.data.store(data, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns how full the table is in permille, as reported by UCI `hashfull`
+    pub fn get_fullness(&self) -> usize {
+        let sample = self.slots.len().min(1000);
+
+        (0..sample)
+            .filter(|index| matches!(self.load(*index), Some(entry) if entry.age == self.age))
+            .count()
+            * 1000
+            / sample
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (key % self.slots.len() as u64) as usize
+    }
+
+    fn load(&self, index: usize) -> Option<Entry> {
+        let slot = &self.slots[index];
+        let data = slot.data.load(Ordering::Relaxed);
+
+        Entry::unpack(slot.key.load(Ordering::Relaxed) ^ data, data)
+    }
+}
+
+impl Clone for TranspositionTable {
+    fn clone(&self) -> Self {
+        let slots = self
+            .slots
+            .iter()
+            .map(|slot| Slot {
+                key: AtomicU64::new(slot.key.load(Ordering::Relaxed)),
+                data: AtomicU64::new(slot.data.load(Ordering::Relaxed)),
+            })
+            .collect();
+
+        Self { slots, age: self.age }
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_HASH_SIZE)
+    }
+}
+
+// Packs a move into 17 bits, the pieces of promotions and drops are numbered from one
+fn pack_move(chess_move: &ChessMove) -> u64 {
+    let piece_number = |piece: &Piece| match piece {
+        Piece::Queen => 1,
+        Piece::Rook => 2,
+        Piece::Bishop => 3,
+        Piece::Knight => 4,
+        Piece::King { .. } => 5,
+        Piece::Pawn { .. } => 6,
+    };
+
+    let packed = match chess_move {
+        ChessMove::Move { from, to, promotion } => {
+            from.index() as u64 | (to.index() as u64) << 6 | promotion.as_ref().map_or(0, piece_number) << 12
+        }
+        ChessMove::Drop { piece, to } => 1 << 15 | (to.index() as u64) << 6 | piece_number(piece) << 12,
+    };
+
+    1 << 16 | packed
+}
+
+// Pieces are unpacked the way they are promoted to and kept in pockets
+fn unpack_move(packed: u64) -> Option<ChessMove> {
+    if packed & 1 << 16 == 0 {
+        return None;
+    }
+
+    let piece = match (packed >> 12) & 7 {
+        1 => Some(Piece::Queen),
+        2 => Some(Piece::Rook),
+        3 => Some(Piece::Bishop),
+        4 => Some(Piece::Knight),
+        5 => Some(Piece::King {
+            check_state: None,
+            castling_state: (false, false),
+        }),
+        6 => Some(Piece::Pawn {
+            state: PawnState::Default,
+        }),
+        _ => None,
+    };
+    let to = BoardPosition::from_index((packed as usize >> 6) & 63);
+
+    if packed & 1 << 15 != 0 {
+        Some(ChessMove::Drop { piece: piece?, to })
+    } else {
+        Some(ChessMove::Move {
+            from: BoardPosition::from_index(packed as usize & 63),
+            to,
+            promotion: piece,
+        })
+    }
+}
+
+// Mate scores are stored as the distance from the position instead of from the root, so they are
+// correct when the position is reached at another ply
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    if score > MATE - MAX_PLY as i32 {
+        score + ply as i32
+    } else if score < -MATE + MAX_PLY as i32 {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    if score > MATE - MAX_PLY as i32 {
+        score - ply as i32
+    } else if score < -MATE + MAX_PLY as i32 {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mate_scores_are_relative_to_the_position() {
+        let table = TranspositionTable::new(1);
+
+        // Mate in one ply from a position found three ply from the root
+        table.store(42, 1, Bound::Exact, MATE - 4, None, 3);
+
+        let entry = table.probe(42).unwrap();
+
+        assert_eq!(entry.get_score(3), MATE - 4);
+        assert_eq!(entry.get_score(1), MATE - 2);
+        assert_eq!(entry.bound, Bound::Exact);
+        assert!(table.probe(43).is_none());
+    }
+
+    #[test]
+    fn deeper_entries_are_kept() {
+        let mut table = TranspositionTable::new(1);
+        let chess_move: ChessMove = "e2e4".parse().unwrap();
+
+        table.store(42, 5, Bound::Lower, 30, Some(chess_move.clone()), 0);
+        table.store(42, 2, Bound::Exact, 10, None, 0);
+
+        assert_eq!(table.probe(42).unwrap().depth, 5);
+
+        table.store(42, 6, Bound::Upper, 20, None, 0);
+
+        assert_eq!(table.probe(42).unwrap().depth, 6);
+        assert_eq!(table.probe(42).unwrap().best_move, Some(chess_move));
+
+        // Entries from an earlier search can always be replaced
+        table.new_search();
+        table.store(42, 1, Bound::Exact, 0, None, 0);
+
+        assert_eq!(table.probe(42).unwrap().depth, 1);
+
+        table.clear();
+
+        assert!(table.probe(42).is_none());
+    }
+
+    #[test]
+    fn moves_are_packed() {
+        for chess_move in ["e2e4", "a7a8q", "h2h1n", "b7b8k", "P@e4", "Q@a1", "N@h8"] {
+            let chess_move: ChessMove = chess_move.parse().unwrap();
+
+            assert_eq!(unpack_move(pack_move(&chess_move)), Some(chess_move));
+        }
+
+        let table = TranspositionTable::new(1);
+        table.store(0, 3, Bound::Upper, -MATE + 10, Some("g1f3".parse().unwrap()), 2);
+
+        let entry = table.probe(0).unwrap();
+
+        assert_eq!(entry.get_score(2), -MATE + 10);
+        assert_eq!(entry.bound, Bound::Upper);
+        assert_eq!(entry.best_move, Some("g1f3".parse().unwrap()));
+    }
+}
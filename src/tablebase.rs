@@ -0,0 +1,709 @@
+//! Probing Syzygy endgame tablebases read from local files.
+//!
+//! WDL tables (`.rtbw`) tell whether a position is won, drawn or lost with perfect play and DTZ
+//! tables (`.rtbz`) give the distance to the next capture or pawn move, which is needed to win
+//! within the fifty move rule. Only standard chess positions without castling rights can be
+//! probed.
+//!
+//! ```rust,no_run
+//! use viktoe_chess::prelude::*;
+//! use viktoe_chess::tablebase::{Tablebase, Wdl};
+//!
+//! let mut tablebase = Tablebase::new();
+//! tablebase.add_directory("syzygy").unwrap();
+//!
+//! let game = ChessGame::from_fen(String::from("8/8/8/8/8/8/2Q5/k1K5 w - - 0 1")).unwrap();
+//!
+//! assert_eq!(tablebase.probe_wdl(&game), Some(Wdl::Win));
+//! ```
+
+mod table;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Neg;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::board::Turn;
+use crate::chess_move::ChessMove;
+use crate::piece::{Color, Piece};
+use crate::ChessGame;
+use table::{Kind, Lookup, Material, Table};
+
+/// The result of a position with perfect play, from the view of the player whos turn it is.
+///
+/// A cursed win is a win that takes too long for the fifty move rule and a blessed loss is a
+/// loss the fifty move rule saves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Wdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+impl Wdl {
+    fn from_value(value: i32) -> Option<Self> {
+        match value {
+            -2 => Some(Wdl::Loss),
+            -1 => Some(Wdl::BlessedLoss),
+            0 => Some(Wdl::Draw),
+            1 => Some(Wdl::CursedWin),
+            2 => Some(Wdl::Win),
+            _ => None,
+        }
+    }
+
+    fn value(self) -> i32 {
+        self as i32 - 2
+    }
+}
+
+/// The result for the other player
+impl Neg for Wdl {
+    type Output = Wdl;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Wdl::Loss => Wdl::Win,
+            Wdl::BlessedLoss => Wdl::CursedWin,
+            Wdl::Draw => Wdl::Draw,
+            Wdl::CursedWin => Wdl::BlessedLoss,
+            Wdl::Win => Wdl::Loss,
+        }
+    }
+}
+
+// A table file found in a directory, read the first time it is probed
+struct TableFile {
+    path: PathBuf,
+    material: Material,
+    kind: Kind,
+    table: OnceLock<Option<Table>>,
+}
+
+impl TableFile {
+    fn get_table(&self) -> Option<&Table> {
+        self.table
+            .get_or_init(|| Table::new(fs::read(&self.path).ok()?, &self.material, self.kind))
+            .as_ref()
+    }
+}
+
+impl fmt::Debug for TableFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TableFile").field("path", &self.path).finish()
+    }
+}
+
+/// A collection of Syzygy tables.
+///
+/// The files are found by name, such as `KQvK.rtbw`, and are read into memory the first time a
+/// position with their material is probed.
+#[derive(Debug, Default)]
+pub struct Tablebase {
+    wdl: HashMap<String, TableFile>,
+    dtz: HashMap<String, TableFile>,
+    max_pieces: usize,
+}
+
+impl Tablebase {
+    /// Creates a tablebase without tables
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the tables in a directory, returning the number of table files found
+    pub fn add_directory(&mut self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let mut count = 0;
+
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+
+            let kind = match path.extension().and_then(|extension| extension.to_str()) {
+                Some("rtbw") => Kind::Wdl,
+                Some("rtbz") => Kind::Dtz,
+                _ => continue,
+            };
+
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+
+            let material = match Material::parse(&name) {
+                Some(material) => material,
+                None => continue,
+            };
+
+            self.max_pieces = self.max_pieces.max(material.pieces[0].len() + material.pieces[1].len());
+
+            let tables = match kind {
+                Kind::Wdl => &mut self.wdl,
+                Kind::Dtz => &mut self.dtz,
+            };
+
+            tables.insert(
+                name,
+                TableFile {
+                    path,
+                    material,
+                    kind,
+                    table: OnceLock::new(),
+                },
+            );
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Returns the largest number of pieces, kings included, of the tables found
+    pub fn get_max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    /// Returns whether the position can be probed, it has to be standard chess without castling
+    /// rights and with few enough pieces
+    pub fn can_probe(&self, game: &ChessGame) -> bool {
+        let mut pieces = 0;
+
+        for piece in game.board.iter().flatten() {
+            if let Piece::King {
+                castling_state: (true, _) | (_, true),
+                ..
+            } = piece.get_internal()
+            {
+                return false;
+            }
+
+            pieces += 1;
+        }
+
+        pieces <= self.max_pieces && game.get_variant().is_standard()
+    }
+
+    /// Returns the result of the position with perfect play, or None if it is not in the tables
+    pub fn probe_wdl(&self, game: &ChessGame) -> Option<Wdl> {
+        if !self.can_probe(game) {
+            return None;
+        }
+
+        self.search(game, false).map(|(wdl, _)| wdl)
+    }
+
+    /// Returns the number of plies to the next capture or pawn move with perfect play, positive
+    /// when the player whos turn it is wins and negative when they lose, or None if the position
+    /// is not in the tables.
+    ///
+    /// Draws are 0 and cursed wins and blessed losses are 100 plies further away than the fifty
+    /// move rule allows. As the tables store moves for most positions the distance can be one
+    /// ply too long.
+    pub fn probe_dtz(&self, game: &ChessGame) -> Option<i32> {
+        if !self.can_probe(game) {
+            return None;
+        }
+
+        self.dtz(game)
+    }
+
+    /// Returns the moves that keep the best result, the ones that reach the next capture or pawn
+    /// move soonest when winning and latest when losing. None if the position is not in the
+    /// tables or there are no moves.
+    ///
+    /// The number of plies since the last capture or pawn move decides whether a win can still be
+    /// reached within the fifty move rule. Without DTZ tables the moves are only ranked by their
+    /// result.
+    pub fn get_best_moves(&self, game: &ChessGame) -> Option<Vec<ChessMove>> {
+        if !self.can_probe(game) {
+            return None;
+        }
+
+        let ranked = self.rank_by_dtz(game).or_else(|| self.rank_by_wdl(game))?;
+        let (best_wdl, best_distance, _) = ranked.iter().max_by_key(|(wdl, distance, _)| (*wdl, *distance))?.clone();
+
+        Some(
+            ranked
+                .into_iter()
+                .filter(|(wdl, distance, _)| *wdl == best_wdl && (*wdl == Wdl::Draw || *distance == best_distance))
+                .map(|(_, _, chess_move)| chess_move)
+                .collect(),
+        )
+    }
+
+    // Ranks the moves by their result and distance, wins by the shortest distance and losses by
+    // the longest
+    fn rank_by_dtz(&self, game: &ChessGame) -> Option<Vec<(Wdl, i32, ChessMove)>> {
+        let half_move = i32::from(game.half_move);
+        let mut ranked = Vec::new();
+
+        for chess_move in game.get_legal_moves() {
+            let mut child = game.clone_position();
+            child.play_unchecked(&chess_move);
+
+            let mut dtz = if is_zeroing(game, &chess_move) {
+                dtz_before_zeroing(-self.search(&child, false)?.0)
+            } else {
+                let dtz = -self.dtz(&child)?;
+                dtz + dtz.signum()
+            };
+
+            // A checkmate is a win in one ply
+            if dtz == 2 && child.is_in_check(child.get_player_turn()) && !child.has_valid_moves() {
+                dtz = 1;
+            }
+
+            let wdl = if dtz > 0 && dtz + half_move <= 99 {
+                Wdl::Win
+            } else if dtz > 0 {
+                Wdl::CursedWin
+            } else if dtz < 0 && half_move - dtz <= 99 {
+                Wdl::Loss
+            } else if dtz < 0 {
+                Wdl::BlessedLoss
+            } else {
+                Wdl::Draw
+            };
+
+            ranked.push((wdl, -dtz.abs() * dtz.signum(), chess_move));
+        }
+
+        Some(ranked)
+    }
+
+    fn rank_by_wdl(&self, game: &ChessGame) -> Option<Vec<(Wdl, i32, ChessMove)>> {
+        let mut ranked = Vec::new();
+
+        for chess_move in game.get_legal_moves() {
+            let mut child = game.clone_position();
+            child.play_unchecked(&chess_move);
+
+            ranked.push((-self.search(&child, false)?.0, 0, chess_move));
+        }
+
+        Some(ranked)
+    }
+
+    // Looks up the position in a table, with the colors swapped if the table has the material
+    // the other way around
+    fn probe_table(&self, game: &ChessGame, kind: Kind, wdl: i32) -> Option<Lookup> {
+        let mut pieces = Vec::new();
+        let mut codes: [Vec<u8>; 2] = [Vec::new(), Vec::new()];
+
+        for (index, piece) in game.board.iter().enumerate() {
+            if let Some(piece) = piece {
+                let (code, side) = match piece {
+                    Color::White(piece) => (piece_code(piece), 0),
+                    Color::Black(piece) => (piece_code(piece) | 8, 1),
+                };
+
+                // The board starts at a8, the tables number the squares from a1
+                pieces.push((index ^ 56, code));
+                codes[side].push(code);
+            }
+        }
+
+        // Only the kings are left
+        if pieces.len() == 2 {
+            return Some(Lookup::Value(0));
+        }
+
+        // Tables are named by the pieces of each side from the king to the pawns, as in KRPvKR
+        let [white, black] = codes.map(|mut side| {
+            side.sort_unstable_by(|first, second| second.cmp(first));
+            side.iter().map(|code| char::from(b"PNBRQK"[usize::from(code & 7) - 1])).collect::<String>()
+        });
+        let name = |first: &str, second: &str| format!("{first}v{second}");
+
+        let tables = match kind {
+            Kind::Wdl => &self.wdl,
+            Kind::Dtz => &self.dtz,
+        };
+
+        let (file, flipped) = match tables.get(&name(&white, &black)) {
+            Some(file) => (file, false),
+            None => (tables.get(&name(&black, &white))?, true),
+        };
+
+        let black_to_move = matches!(game.get_player_turn(), Turn::Black);
+
+        file.get_table()?.probe(&pieces, black_to_move, flipped, wdl)
+    }
+
+    // Probes the position after trying the captures, as the tables leave out positions where a
+    // capture is best. With `check_zeroing` pawn moves are tried as well, returning whether the
+    // best move resets the fifty move counter.
+    fn search(&self, game: &ChessGame, check_zeroing: bool) -> Option<(Wdl, bool)> {
+        let moves = game.get_legal_moves();
+        let total = moves.len();
+        let mut count = 0;
+        let mut best = None;
+
+        for chess_move in moves {
+            let capture = is_capture(game, &chess_move);
+
+            if !capture && (!check_zeroing || !is_zeroing(game, &chess_move)) {
+                continue;
+            }
+
+            count += 1;
+
+            let mut child = game.clone_position();
+            child.play_unchecked(&chess_move);
+
+            let wdl = -self.search(&child, false)?.0;
+
+            if best.is_none_or(|best| wdl > best) {
+                best = Some(wdl);
+
+                if wdl == Wdl::Win {
+                    return Some((wdl, true));
+                }
+            }
+        }
+
+        // Without moves left the tables are not needed, as the captures decide the result
+        let no_more_moves = count > 0 && count == total;
+
+        let wdl = if total == 0 {
+            if game.is_in_check(game.get_player_turn()) {
+                Wdl::Loss
+            } else {
+                Wdl::Draw
+            }
+        } else if no_more_moves {
+            best?
+        } else {
+            match self.probe_table(game, Kind::Wdl, 0)? {
+                Lookup::Value(value) => Wdl::from_value(value)?,
+                Lookup::ChangeSideToMove => return None,
+            }
+        };
+
+        match best {
+            Some(best) if best >= wdl => Some((best, best > Wdl::Draw || no_more_moves)),
+            _ => Some((wdl, false)),
+        }
+    }
+
+    fn dtz(&self, game: &ChessGame) -> Option<i32> {
+        let (wdl, zeroing) = self.search(game, true)?;
+
+        if wdl == Wdl::Draw {
+            return Some(0);
+        }
+
+        if zeroing {
+            return Some(dtz_before_zeroing(wdl));
+        }
+
+        let sign = wdl.value().signum();
+
+        match self.probe_table(game, Kind::Dtz, wdl.value())? {
+            Lookup::Value(dtz) => {
+                let cursed = if matches!(wdl, Wdl::CursedWin | Wdl::BlessedLoss) { 100 } else { 0 };
+
+                Some((dtz + cursed) * sign)
+            }
+            // The table stores the other side to move, so the moves are tried one ply deeper
+            Lookup::ChangeSideToMove => {
+                let mut best = None;
+
+                for chess_move in game.get_legal_moves() {
+                    let zeroing = is_zeroing(game, &chess_move);
+
+                    let mut child = game.clone_position();
+                    child.play_unchecked(&chess_move);
+
+                    let mut dtz = if zeroing {
+                        -dtz_before_zeroing(self.search(&child, false)?.0)
+                    } else {
+                        -self.dtz(&child)?
+                    };
+
+                    // A checkmate is the shortest win
+                    if dtz == 1 && child.is_in_check(child.get_player_turn()) && !child.has_valid_moves() {
+                        best = Some(1);
+                    }
+
+                    if !zeroing {
+                        dtz += dtz.signum();
+                    }
+
+                    if dtz.signum() == sign && best.is_none_or(|best| dtz < best) {
+                        best = Some(dtz);
+                    }
+                }
+
+                Some(best.unwrap_or(-1))
+            }
+        }
+    }
+}
+
+// The distance of a position where the best move resets the fifty move counter
+fn dtz_before_zeroing(wdl: Wdl) -> i32 {
+    match wdl {
+        Wdl::Loss => -1,
+        Wdl::BlessedLoss => -101,
+        Wdl::Draw => 0,
+        Wdl::CursedWin => 101,
+        Wdl::Win => 1,
+    }
+}
+
+fn piece_code(piece: &Piece) -> u8 {
+    match piece {
+        Piece::Pawn { .. } => 1,
+        Piece::Knight => 2,
+        Piece::Bishop => 3,
+        Piece::Rook => 4,
+        Piece::Queen => 5,
+        Piece::King { .. } => 6,
+    }
+}
+
+fn is_capture(game: &ChessGame, chess_move: &ChessMove) -> bool {
+    match chess_move {
+        ChessMove::Move { from, to, .. } => {
+            game.get_square(to).is_some()
+                || (matches!(game.get_square(from).as_ref().map(Color::get_internal), Some(Piece::Pawn { .. }))
+                    && from.get_file() != to.get_file())
+        }
+        ChessMove::Drop { .. } => false,
+    }
+}
+
+// Captures and pawn moves reset the fifty move counter
+fn is_zeroing(game: &ChessGame, chess_move: &ChessMove) -> bool {
+    match chess_move {
+        ChessMove::Move { from, .. } => {
+            is_capture(game, chess_move)
+                || matches!(game.get_square(from).as_ref().map(Color::get_internal), Some(Piece::Pawn { .. }))
+        }
+        ChessMove::Drop { .. } => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::table::tests::synthetic_table;
+    use super::*;
+    use crate::endgame::EndgameSolver;
+    use crate::search::{Engine, Limits, Score};
+    use crate::variant::{Atomic, Variant};
+    use std::sync::Arc;
+
+    // A directory of synthetic tables, removed when the test ends
+    struct Directory(PathBuf);
+
+    impl Drop for Directory {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // Writes synthetic tables to a new directory, every position is a win for the player to move
+    // when `value` is true for its index
+    fn tablebase(name: &str, tables: &[&str], value: fn(u64) -> bool) -> (Tablebase, Directory) {
+        let directory = Directory(std::env::temp_dir().join(format!("viktoe-chess-{name}-{}", std::process::id())));
+        fs::create_dir_all(&directory.0).unwrap();
+
+        for table in tables {
+            let material = Material::parse(table).unwrap();
+            fs::write(directory.0.join(format!("{table}.rtbw")), synthetic_table(&material, value)).unwrap();
+        }
+
+        let mut tablebase = Tablebase::new();
+
+        assert_eq!(tablebase.add_directory(&directory.0).unwrap(), tables.len());
+
+        (tablebase, directory)
+    }
+
+    fn game(fen: &str) -> ChessGame {
+        ChessGame::from_fen(String::from(fen)).unwrap()
+    }
+
+    #[test]
+    fn reads_names_and_limits() {
+        assert_eq!(Material::parse("KRPvKR").unwrap().pieces, [vec![6, 4, 1], vec![14, 12]]);
+        assert!(Material::parse("KQvQ").is_none());
+        assert!(Material::parse("KQQQQQQvK").is_none());
+
+        let (tablebase, _directory) = tablebase("limits", &["KQvK"], |_| true);
+
+        assert_eq!(tablebase.get_max_pieces(), 3);
+
+        // Too many pieces, castling rights and other variants can not be probed
+        assert_eq!(tablebase.probe_wdl(&game("8/8/8/8/8/8/2QQ4/k1K5 w - - 0 1")), None);
+        assert_eq!(tablebase.probe_wdl(&game("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1")), None);
+        assert_eq!(
+            tablebase.probe_wdl(&ChessGame::from_fen_with_variant(String::from("8/8/8/8/8/8/2Q5/k1K5 w - - 0 1"), Atomic).unwrap()),
+            None
+        );
+
+        // A variant is known by its type and not by its name
+        struct Renamed;
+
+        impl Variant for Renamed {
+            fn name(&self) -> &'static str {
+                "standard"
+            }
+        }
+
+        assert!(!tablebase.can_probe(&ChessGame::from_fen_with_variant(String::from("8/8/8/8/8/8/2Q5/k1K5 w - - 0 1"), Renamed).unwrap()));
+
+        // Two kings are a draw without a table
+        assert_eq!(tablebase.probe_wdl(&game("8/8/8/8/8/8/8/k1K5 w - - 0 1")), Some(Wdl::Draw));
+
+        // The table is missing
+        assert_eq!(tablebase.probe_wdl(&game("8/8/8/8/8/8/2R5/k1K5 w - - 0 1")), None);
+        assert_eq!(Tablebase::new().probe_wdl(&game("8/8/8/8/8/8/2Q5/k1K5 w - - 0 1")), None);
+    }
+
+    #[test]
+    fn probes_both_colors_and_captures() {
+        let (tablebase, _directory) = tablebase("colors", &["KQvK", "KPvK"], |index| index % 3 != 0);
+
+        for fen in [
+            "8/8/8/3k4/8/8/2Q5/K7 w - - 0 1",
+            "8/8/8/3k4/8/8/2Q5/K7 b - - 0 1",
+            "8/8/2k5/8/8/3Q4/8/6K1 b - - 0 1",
+            "8/8/2k5/8/8/3P4/8/6K1 w - - 0 1",
+            "8/8/2k5/8/8/3P4/8/6K1 b - - 0 1",
+        ] {
+            let white = game(fen);
+
+            // The same position with the colors swapped
+            let (board, turn) = fen.split_once(' ').unwrap();
+            let mirrored_board: Vec<String> = board
+                .split('/')
+                .rev()
+                .map(|rank| rank.chars().map(|char| if char.is_ascii_uppercase() { char.to_ascii_lowercase() } else { char.to_ascii_uppercase() }).collect())
+                .collect();
+            let turn = if turn.starts_with('w') { "b - - 0 1" } else { "w - - 0 1" };
+            let black = game(&format!("{} {turn}", mirrored_board.join("/")));
+
+            let wdl = tablebase.probe_wdl(&white);
+
+            assert!(matches!(wdl, Some(Wdl::Win | Wdl::Loss)), "{fen}");
+            assert_eq!(tablebase.probe_wdl(&black), wdl, "{fen}");
+        }
+
+        // The king takes the queen
+        assert_eq!(tablebase.probe_wdl(&game("8/8/8/8/8/8/1Q6/k5K1 b - - 0 1")), Some(Wdl::Draw));
+    }
+
+    #[test]
+    fn best_moves_keep_the_win() {
+        let (tablebase, _directory) = tablebase("moves", &["KQvK"], |index| index % 5 == 0);
+        let game = game("8/8/8/8/8/8/2Q5/k5K1 w - - 0 1");
+
+        // Without DTZ tables the moves are ranked by the result
+        assert_eq!(tablebase.probe_dtz(&game), None);
+
+        let results: Vec<(ChessMove, Wdl)> = game
+            .get_legal_moves()
+            .into_iter()
+            .map(|chess_move| {
+                let mut child = game.clone();
+                child.make_move(&chess_move).unwrap();
+
+                (chess_move, -tablebase.probe_wdl(&child).unwrap())
+            })
+            .collect();
+
+        let best = results.iter().map(|(_, wdl)| *wdl).max().unwrap();
+        let best_moves = tablebase.get_best_moves(&game).unwrap();
+
+        assert!(!best_moves.is_empty());
+        assert!(results.iter().all(|(chess_move, wdl)| best_moves.contains(chess_move) == (*wdl == best)));
+
+        // The search only plays the best moves
+        let mut engine = Engine::default();
+        engine.set_tablebase(Some(Arc::new(tablebase)));
+
+        let result = engine.search(&game, &Limits {
+            depth: Some(2),
+            ..Limits::default()
+        });
+
+        assert!(best_moves.contains(&result.best_move.unwrap()));
+        assert!(result.tablebase_hits > 0);
+    }
+
+    #[test]
+    #[ignore = "needs the KQvK and KRvK WDL and DTZ files of the real tables in SYZYGY_PATH"]
+    fn probes_real_tables() {
+        let path = std::env::var("SYZYGY_PATH").expect("SYZYGY_PATH is not set");
+        let mut tablebase = Tablebase::new();
+
+        assert!(tablebase.add_directory(path).unwrap() >= 4);
+
+        // Mates in one
+        for fen in ["8/8/8/8/8/8/2Q5/k1K5 w - - 0 1", "k7/8/1K6/8/8/8/8/7R w - - 0 1"] {
+            assert_eq!(tablebase.probe_wdl(&game(fen)), Some(Wdl::Win), "{fen}");
+            assert_eq!(tablebase.probe_dtz(&game(fen)), Some(1), "{fen}");
+        }
+
+        assert_eq!(tablebase.probe_wdl(&game("k7/8/1K6/8/8/8/8/7R b - - 0 1")), Some(Wdl::Loss));
+        assert_eq!(tablebase.probe_wdl(&game("8/8/8/8/8/8/1Q6/k5K1 b - - 0 1")), Some(Wdl::Draw));
+        assert_eq!(tablebase.probe_wdl(&game("8/8/8/8/8/8/1R6/k5K1 b - - 0 1")), Some(Wdl::Draw));
+
+        // Without pawns the distance to the next capture is the distance to mate, which the
+        // endgame solver finds on its own. Every square of the weaker king is compared.
+        let solver = EndgameSolver::new();
+
+        for (piece, turn) in [('Q', 'w'), ('Q', 'b'), ('R', 'w'), ('R', 'b')] {
+            for square in 0..64 {
+                let mut ranks = [[' '; 8]; 8];
+                ranks[5][2] = 'K';
+                ranks[6][5] = piece;
+
+                if ranks[square / 8][square % 8] != ' ' {
+                    continue;
+                }
+
+                ranks[square / 8][square % 8] = 'k';
+
+                let board: Vec<String> = ranks
+                    .iter()
+                    .map(|rank| {
+                        let rank: String = rank.iter().collect();
+
+                        (1..=8).rev().fold(rank, |rank, empty| rank.replace(&" ".repeat(empty), &empty.to_string()))
+                    })
+                    .collect();
+                let fen = format!("{} {turn} - - 0 1", board.join("/"));
+
+                let game = match ChessGame::from_fen(fen.clone()) {
+                    Ok(game) if !game.get_legal_moves().is_empty() => game,
+                    _ => continue,
+                };
+
+                let solution = match solver.probe(&game) {
+                    Some(solution) => solution,
+                    None => continue,
+                };
+
+                let (wdl, plies) = match solution.score {
+                    Score::Mate(moves) if moves > 0 => (Wdl::Win, moves * 2 - 1),
+                    Score::Mate(moves) => (Wdl::Loss, moves * 2),
+                    Score::Centipawns(_) => (Wdl::Draw, 0),
+                };
+
+                assert_eq!(tablebase.probe_wdl(&game), Some(wdl), "{fen}");
+
+                // The tables can store a distance one ply too long
+                let dtz = tablebase.probe_dtz(&game).unwrap();
+
+                assert!(dtz == plies || dtz == plies + plies.signum(), "{fen}: {dtz} instead of {plies}");
+            }
+        }
+    }
+}
@@ -0,0 +1,1055 @@
+// A single Syzygy table file, the format and the encoding follow the probing code published
+// with the tables by Ronald de Man.
+//
+// Squares are numbered from a1 = 0 to h8 = 63 and pieces use the codes of the files, 1 to 6 for
+// the white pawn to king and 9 to 14 for the black pawn to king.
+
+use std::sync::OnceLock;
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xe8, 0x23, 0x5d];
+const DTZ_MAGIC: [u8; 4] = [0xd7, 0x66, 0x0c, 0xa5];
+
+// The flags of the compressed data of a table
+const STM: u8 = 1;
+const MAPPED: u8 = 2;
+const WIN_PLIES: u8 = 4;
+const LOSS_PLIES: u8 = 8;
+const WIDE: u8 = 16;
+const SINGLE_VALUE: u8 = 128;
+
+// The flags of a file
+const SPLIT: u8 = 1;
+const HAS_PAWNS: u8 = 2;
+
+pub(super) const MAX_PIECES: usize = 7;
+
+const PAWN: u8 = 1;
+
+/// Whether a table stores win, draw or loss or the distance to zeroing the fifty move counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Kind {
+    Wdl,
+    Dtz,
+}
+
+/// The material of a table, the pieces of each side from the name of the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Material {
+    /// The pieces of the side before the `v`, then after it, as piece codes
+    pub pieces: [Vec<u8>; 2],
+}
+
+impl Material {
+    /// Parses a name like `KRPvKR`
+    pub fn parse(name: &str) -> Option<Self> {
+        let (first, second) = name.split_once('v')?;
+
+        let parse_side = |side: &str, color: u8| -> Option<Vec<u8>> {
+            side.chars()
+                .map(|char| {
+                    let kind = match char {
+                        'P' => 1,
+                        'N' => 2,
+                        'B' => 3,
+                        'R' => 4,
+                        'Q' => 5,
+                        'K' => 6,
+                        _ => return None,
+                    };
+
+                    Some(kind | color)
+                })
+                .collect()
+        };
+
+        let pieces = [parse_side(first, 0)?, parse_side(second, 8)?];
+
+        let kings = |side: &Vec<u8>| side.iter().filter(|piece| **piece & 7 == 6).count();
+
+        if kings(&pieces[0]) != 1 || kings(&pieces[1]) != 1 || pieces[0].len() + pieces[1].len() > MAX_PIECES {
+            return None;
+        }
+
+        Some(Self { pieces })
+    }
+
+    fn count(&self, piece: u8) -> usize {
+        let side = usize::from(piece >> 3);
+
+        self.pieces[side].iter().filter(|code| **code == piece).count()
+    }
+}
+
+// The tables used to turn a position into an index
+struct Indices {
+    map_pawns: [u64; 64],
+    map_b1h1h7: [u64; 64],
+    map_a1d1d4: [u64; 64],
+    map_kk: [[u64; 64]; 10],
+    binomial: [[u64; 64]; 7],
+    lead_pawn_index: [[u64; 64]; 6],
+    lead_pawns_size: [[u64; 4]; 6],
+}
+
+fn rank(square: usize) -> usize {
+    square >> 3
+}
+
+fn file(square: usize) -> usize {
+    square & 7
+}
+
+// Negative below the a1-h8 diagonal, zero on it and positive above it
+fn off_diagonal(square: usize) -> i32 {
+    rank(square) as i32 - file(square) as i32
+}
+
+fn indices() -> &'static Indices {
+    static INDICES: OnceLock<Indices> = OnceLock::new();
+
+    INDICES.get_or_init(|| {
+        let mut indices = Indices {
+            map_pawns: [0; 64],
+            map_b1h1h7: [0; 64],
+            map_a1d1d4: [0; 64],
+            map_kk: [[0; 64]; 10],
+            binomial: [[0; 64]; 7],
+            lead_pawn_index: [[0; 64]; 6],
+            lead_pawns_size: [[0; 4]; 6],
+        };
+
+        // The squares below the a1-h8 diagonal are numbered 0 to 27
+        let mut code = 0;
+        for square in 0..64 {
+            if off_diagonal(square) < 0 {
+                indices.map_b1h1h7[square] = code;
+                code += 1;
+            }
+        }
+
+        // The squares of the a1-d1-d4 triangle are numbered 0 to 9, the ones on the diagonal last
+        let mut code = 0;
+        let mut diagonal = Vec::new();
+        for square in [0, 1, 2, 3, 8, 9, 10, 11, 16, 17, 18, 19, 24, 25, 26, 27] {
+            if off_diagonal(square) < 0 {
+                indices.map_a1d1d4[square] = code;
+                code += 1;
+            } else if off_diagonal(square) == 0 {
+                diagonal.push(square);
+            }
+        }
+        for square in diagonal {
+            indices.map_a1d1d4[square] = code;
+            code += 1;
+        }
+
+        // The 462 ways to place two kings with the first in the a1-d1-d4 triangle, when the first
+        // is on the diagonal the second is not above it
+        let mut code = 0;
+        let mut both_on_diagonal = Vec::new();
+        for index in 0..10 {
+            for first in 0..28 {
+                if indices.map_a1d1d4[first] != index || (index == 0 && first != 1) {
+                    continue;
+                }
+
+                for second in 0..64 {
+                    let adjacent = file(first).abs_diff(file(second)) <= 1 && rank(first).abs_diff(rank(second)) <= 1;
+
+                    if adjacent || (off_diagonal(first) == 0 && off_diagonal(second) > 0) {
+                        continue;
+                    }
+
+                    if off_diagonal(first) == 0 && off_diagonal(second) == 0 {
+                        both_on_diagonal.push((index, second));
+                    } else {
+                        indices.map_kk[index as usize][second] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+        for (index, second) in both_on_diagonal {
+            indices.map_kk[index as usize][second] = code;
+            code += 1;
+        }
+
+        // binomial[k][n] is the number of ways to choose k of n elements
+        indices.binomial[0][0] = 1;
+        for n in 1..64 {
+            for k in 0..7.min(n + 1) {
+                indices.binomial[k][n] = if k > 0 { indices.binomial[k - 1][n - 1] } else { 0 }
+                    + if k < n { indices.binomial[k][n - 1] } else { 0 };
+            }
+        }
+
+        // The pawn with the highest number is the leading pawn, the one closest to the edge and
+        // among those the one on the lowest rank
+        let mut available_squares = 47;
+
+        for lead_pawns in 1..6 {
+            for lead_file in 0..4 {
+                let mut index = 0;
+
+                for lead_rank in 1..7 {
+                    let square = lead_file + lead_rank * 8;
+
+                    if lead_pawns == 1 {
+                        indices.map_pawns[square] = available_squares;
+                        available_squares = available_squares.saturating_sub(1);
+                        indices.map_pawns[square ^ 7] = available_squares;
+                        available_squares = available_squares.saturating_sub(1);
+                    }
+
+                    indices.lead_pawn_index[lead_pawns][square] = index;
+                    index += indices.binomial[lead_pawns - 1][indices.map_pawns[square] as usize];
+                }
+
+                indices.lead_pawns_size[lead_pawns][lead_file] = index;
+            }
+        }
+
+        indices
+    })
+}
+
+// The compressed data of one side of a table, for one file of the leading pawn
+#[derive(Debug, Clone, Default)]
+struct PairsData {
+    flags: u8,
+    min_symbol_length: u8,
+    block_size: u64,
+    span: u64,
+    block_count: u64,
+    lowest_symbols: usize,
+    tree: usize,
+    block_lengths: usize,
+    block_length_size: u64,
+    sparse_index: usize,
+    sparse_index_size: u64,
+    data: usize,
+    base: Vec<u64>,
+    symbol_lengths: Vec<u8>,
+    pieces: [u8; MAX_PIECES],
+    group_index: [u64; MAX_PIECES + 1],
+    group_length: [usize; MAX_PIECES + 1],
+    map_index: [u64; 4],
+}
+
+/// The result of looking up a position in a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Lookup {
+    Value(i32),
+    /// DTZ tables only store one side to move, the position has to be probed a move deeper
+    ChangeSideToMove,
+}
+
+/// A table read from a file, the pieces of the position are given as `(square, piece)` pairs
+pub(super) struct Table {
+    bytes: Vec<u8>,
+    kind: Kind,
+    symmetric: bool,
+    piece_count: usize,
+    has_pawns: bool,
+    has_unique_pieces: bool,
+    // The number of pawns of the leading color and of the other color
+    pawn_count: [usize; 2],
+    // Indexed by the file of the leading pawn, then the side to move
+    items: Vec<Vec<PairsData>>,
+    map: usize,
+}
+
+fn byte(bytes: &[u8], offset: usize) -> Option<u8> {
+    bytes.get(offset).copied()
+}
+
+fn u16_le(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn u32_be(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn u64_be(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(bytes.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+// The two symbols a symbol of the pairs tree expands to, 12 bits each
+fn tree_left(bytes: &[u8], tree: usize, symbol: usize) -> Option<usize> {
+    let offset = tree + symbol * 3;
+
+    Some(usize::from(byte(bytes, offset + 1)? & 0xf) << 8 | usize::from(byte(bytes, offset)?))
+}
+
+fn tree_right(bytes: &[u8], tree: usize, symbol: usize) -> Option<usize> {
+    let offset = tree + symbol * 3;
+
+    Some(usize::from(byte(bytes, offset + 2)?) << 4 | usize::from(byte(bytes, offset + 1)? >> 4))
+}
+
+impl Table {
+    /// Reads a table, returning None if the file does not match the material or is damaged
+    pub fn new(bytes: Vec<u8>, material: &Material, kind: Kind) -> Option<Self> {
+        let magic = match kind {
+            Kind::Wdl => WDL_MAGIC,
+            Kind::Dtz => DTZ_MAGIC,
+        };
+
+        if bytes.get(0..4)? != magic {
+            return None;
+        }
+
+        let white_pawns = material.count(PAWN);
+        let black_pawns = material.count(PAWN | 8);
+
+        // The color with fewer pawns leads, as it compresses better
+        let white_leads = black_pawns == 0 || (white_pawns > 0 && black_pawns >= white_pawns);
+
+        let mut table = Self {
+            bytes,
+            kind,
+            symmetric: material.pieces[0] == material.pieces[1].iter().map(|piece| piece ^ 8).collect::<Vec<u8>>(),
+            piece_count: material.pieces[0].len() + material.pieces[1].len(),
+            has_pawns: white_pawns + black_pawns > 0,
+            has_unique_pieces: (1..6).any(|kind| material.count(kind) == 1 || material.count(kind | 8) == 1),
+            pawn_count: if white_leads {
+                [white_pawns, black_pawns]
+            } else {
+                [black_pawns, white_pawns]
+            },
+            items: Vec::new(),
+            map: 0,
+        };
+
+        table.read()?;
+
+        Some(table)
+    }
+
+    fn read(&mut self) -> Option<()> {
+        let flags = byte(&self.bytes, 4)?;
+
+        if (flags & HAS_PAWNS != 0) != self.has_pawns || (flags & SPLIT != 0) == self.symmetric {
+            return None;
+        }
+
+        let sides = if self.kind == Kind::Wdl && !self.symmetric { 2 } else { 1 };
+        let files = if self.has_pawns { 4 } else { 1 };
+        let pawns_on_both_sides = self.has_pawns && self.pawn_count[1] > 0;
+
+        self.items = vec![vec![PairsData::default(); sides]; files];
+
+        let mut offset = 5;
+
+        for file in 0..files {
+            let first = byte(&self.bytes, offset)?;
+            let second = if pawns_on_both_sides { byte(&self.bytes, offset + 1)? } else { 0xff };
+
+            let order = [[first & 0xf, second & 0xf], [first >> 4, second >> 4]];
+            offset += 1 + usize::from(pawns_on_both_sides);
+
+            for piece in 0..self.piece_count {
+                let codes = byte(&self.bytes, offset)?;
+
+                for (side, item) in self.items[file].iter_mut().enumerate() {
+                    item.pieces[piece] = if side == 1 { codes >> 4 } else { codes & 0xf };
+                }
+
+                offset += 1;
+            }
+
+            for (side, order) in order.into_iter().enumerate().take(sides) {
+                self.set_groups(file, side, order)?;
+            }
+        }
+
+        offset += offset & 1;
+
+        for file in 0..files {
+            for side in 0..sides {
+                offset = self.set_sizes(file, side, offset)?;
+            }
+        }
+
+        if self.kind == Kind::Dtz {
+            offset = self.set_dtz_map(files, offset)?;
+        }
+
+        for item in self.items.iter_mut().flatten() {
+            item.sparse_index = offset;
+            offset += usize::try_from(item.sparse_index_size).ok()? * 6;
+        }
+
+        for item in self.items.iter_mut().flatten() {
+            item.block_lengths = offset;
+            offset += usize::try_from(item.block_length_size).ok()? * 2;
+        }
+
+        for item in self.items.iter_mut().flatten() {
+            offset = (offset + 0x3f) & !0x3f;
+            item.data = offset;
+            offset += usize::try_from(item.block_count * item.block_size).ok()?;
+        }
+
+        (offset <= self.bytes.len()).then_some(())
+    }
+
+    // Splits the pieces into groups of the same piece, each group is encoded on its own and the
+    // order of the groups is stored in the file
+    fn set_groups(&mut self, file: usize, side: usize, order: [u8; 2]) -> Option<()> {
+        let indices = indices();
+        let (has_pawns, has_unique_pieces, piece_count) = (self.has_pawns, self.has_unique_pieces, self.piece_count);
+        let pawns_on_both_sides = has_pawns && self.pawn_count[1] > 0;
+        let item = &mut self.items[file][side];
+
+        let mut first_length: i32 = if has_pawns {
+            0
+        } else if has_unique_pieces {
+            3
+        } else {
+            2
+        };
+
+        let mut groups = 0;
+        item.group_length[0] = 1;
+
+        for piece in 1..piece_count {
+            first_length -= 1;
+
+            if first_length > 0 || item.pieces[piece] == item.pieces[piece - 1] {
+                item.group_length[groups] += 1;
+            } else {
+                groups += 1;
+                item.group_length[groups] = 1;
+            }
+        }
+
+        groups += 1;
+        item.group_length[groups] = 0;
+
+        let mut next = if pawns_on_both_sides { 2 } else { 1 };
+        let mut free_squares =
+            64 - item.group_length[0] - if pawns_on_both_sides { item.group_length[1] } else { 0 };
+        let mut index: u64 = 1;
+        let mut k = 0;
+
+        while next < groups || k == order[0] || k == order[1] {
+            if k == order[0] {
+                item.group_index[0] = index;
+                index *= if has_pawns {
+                    *indices.lead_pawns_size.get(item.group_length[0])?.get(file)?
+                } else if has_unique_pieces {
+                    31332
+                } else {
+                    462
+                };
+            } else if k == order[1] {
+                item.group_index[1] = index;
+                index *= indices.binomial.get(item.group_length[1])?[48 - item.group_length[0]];
+            } else {
+                item.group_index[next] = index;
+                index *= indices.binomial.get(item.group_length[next])?[free_squares];
+                free_squares = free_squares.checked_sub(item.group_length[next])?;
+                next += 1;
+            }
+
+            k += 1;
+
+            if k > 0xf {
+                return None;
+            }
+        }
+
+        item.group_index[groups] = index;
+
+        Some(())
+    }
+
+    // Reads the sizes of the compressed data and the canonical Huffman code of the symbols
+    fn set_sizes(&mut self, file: usize, side: usize, mut offset: usize) -> Option<usize> {
+        let bytes = &self.bytes;
+        let item = &mut self.items[file][side];
+
+        item.flags = byte(bytes, offset)?;
+        offset += 1;
+
+        if item.flags & SINGLE_VALUE != 0 {
+            // The single value is stored as the minimum symbol length
+            item.min_symbol_length = byte(bytes, offset)?;
+            return Some(offset + 1);
+        }
+
+        let groups = item.group_length.iter().position(|length| *length == 0)?;
+        let size = item.group_index[groups];
+
+        item.block_size = 1u64.checked_shl(u32::from(byte(bytes, offset)?))?;
+        item.span = 1u64.checked_shl(u32::from(byte(bytes, offset + 1)?))?;
+        item.sparse_index_size = size.div_ceil(item.span);
+        let padding = u64::from(byte(bytes, offset + 2)?);
+        item.block_count = u64::from(u32_le(bytes, offset + 3)?);
+        item.block_length_size = item.block_count + padding;
+        let max_symbol_length = byte(bytes, offset + 7)?;
+        item.min_symbol_length = byte(bytes, offset + 8)?;
+        offset += 9;
+
+        let lengths = usize::from(max_symbol_length.checked_sub(item.min_symbol_length)?) + 1;
+        item.lowest_symbols = offset;
+        item.base = vec![0; lengths];
+
+        // Longer codes have lower values, base[i] is the lowest code of length i padded to 64 bits
+        for length in (0..lengths - 1).rev() {
+            let lowest = u64::from(u16_le(bytes, offset + length * 2)?);
+            let next_lowest = u64::from(u16_le(bytes, offset + length * 2 + 2)?);
+
+            item.base[length] = item.base[length + 1].wrapping_add(lowest).wrapping_sub(next_lowest) / 2;
+        }
+
+        for (length, base) in item.base.iter_mut().enumerate() {
+            let shift = 64 - length as u32 - u32::from(item.min_symbol_length);
+            *base = base.checked_shl(shift).unwrap_or(0);
+        }
+
+        offset += lengths * 2;
+
+        let symbols = usize::from(u16_le(bytes, offset)?);
+        offset += 2;
+        item.tree = offset;
+        item.symbol_lengths = vec![0; symbols];
+
+        let mut visited = vec![false; symbols];
+
+        for symbol in 0..symbols {
+            if !visited[symbol] {
+                item.symbol_lengths[symbol] =
+                    symbol_length(bytes, item.tree, symbol, &mut item.symbol_lengths, &mut visited)?;
+            }
+        }
+
+        Some(offset + symbols * 3 + (symbols & 1))
+    }
+
+    // DTZ tables can map the stored values to the real distances, one map for each result
+    fn set_dtz_map(&mut self, files: usize, mut offset: usize) -> Option<usize> {
+        self.map = offset;
+
+        for file in 0..files {
+            let item = &mut self.items[file][0];
+
+            if item.flags & MAPPED == 0 {
+                continue;
+            }
+
+            if item.flags & WIDE != 0 {
+                offset += offset & 1;
+
+                for map_index in &mut item.map_index {
+                    *map_index = ((offset - self.map) / 2 + 1) as u64;
+                    offset += 2 * usize::from(u16_le(&self.bytes, offset)?) + 2;
+                }
+            } else {
+                for map_index in &mut item.map_index {
+                    *map_index = (offset - self.map + 1) as u64;
+                    offset += usize::from(byte(&self.bytes, offset)?) + 1;
+                }
+            }
+        }
+
+        Some(offset + (offset & 1))
+    }
+
+    /// Looks up the value of a position, `flipped` is set when the position has the colors of
+    /// the table swapped. The WDL result is needed to read DTZ tables.
+    pub fn probe(&self, pieces: &[(usize, u8)], black_to_move: bool, flipped: bool, wdl: i32) -> Option<Lookup> {
+        let (side, file, index) = match self.encode(pieces, black_to_move, flipped)? {
+            Some(encoded) => encoded,
+            None => return Some(Lookup::ChangeSideToMove),
+        };
+
+        let value = i32::from(self.decompress(&self.items[file][side], index)?);
+
+        Some(Lookup::Value(match self.kind {
+            Kind::Wdl => value - 2,
+            Kind::Dtz => self.map_score(file, value, wdl)?,
+        }))
+    }
+
+    // Returns the side, the file of the leading pawn and the index of a position, or None if a
+    // DTZ table does not store the side to move
+    fn encode(&self, pieces: &[(usize, u8)], black_to_move: bool, flipped: bool) -> Option<Option<(usize, usize, u64)>> {
+        let indices = indices();
+
+        // Symmetric tables only store white to move and tables are stored with the stronger side
+        // as white, other positions are looked up with the colors swapped and the board mirrored
+        let flip = (self.symmetric && black_to_move) || flipped;
+        let flip_color = if flip { 8 } else { 0 };
+        let flip_squares = if flip { 56 } else { 0 };
+        let side_to_move = usize::from(flip ^ black_to_move);
+
+        let mut squares = [0; MAX_PIECES];
+        let mut codes = [0; MAX_PIECES];
+        let mut size = 0;
+        let mut lead_pawns = 0;
+        let mut lead_file = 0;
+
+        // Pawns come first in every file of the table, and their color is the leading color
+        if self.has_pawns {
+            let lead_pawn = self.items[0][0].pieces[0] ^ flip_color;
+
+            for (square, piece) in pieces {
+                if *piece == lead_pawn {
+                    squares[size] = square ^ flip_squares;
+                    size += 1;
+                }
+            }
+
+            lead_pawns = size;
+
+            let mut highest = 0;
+            for index in 1..lead_pawns {
+                if indices.map_pawns[squares[index]] > indices.map_pawns[squares[highest]] {
+                    highest = index;
+                }
+            }
+            squares.swap(0, highest);
+
+            lead_file = file(squares[0]).min(7 - file(squares[0]));
+        }
+
+        let sides = self.items[lead_file].len();
+        let item = &self.items[lead_file][side_to_move % sides];
+
+        if self.kind == Kind::Dtz
+            && usize::from(item.flags & STM) != side_to_move
+            && (!self.symmetric || self.has_pawns)
+        {
+            return Some(None);
+        }
+
+        let lead_pawn = self.has_pawns.then(|| self.items[0][0].pieces[0] ^ flip_color);
+
+        for (square, piece) in pieces {
+            if Some(*piece) != lead_pawn {
+                *squares.get_mut(size)? = square ^ flip_squares;
+                codes[size] = piece ^ flip_color;
+                size += 1;
+            }
+        }
+
+        if size != self.piece_count {
+            return None;
+        }
+
+        // Order the pieces as the table does
+        for i in lead_pawns..size.saturating_sub(1) {
+            for j in i + 1..size {
+                if item.pieces[i] == codes[j] {
+                    codes.swap(i, j);
+                    squares.swap(i, j);
+                    break;
+                }
+            }
+        }
+
+        // Mirror the board so the first piece is on the a to d files
+        if file(squares[0]) > 3 {
+            for square in &mut squares[..size] {
+                *square ^= 7;
+            }
+        }
+
+        let mut index;
+
+        if self.has_pawns {
+            index = indices.lead_pawn_index.get(lead_pawns)?[squares[0]];
+
+            squares[1..lead_pawns].sort_by_key(|square| indices.map_pawns[*square]);
+
+            for (i, square) in squares.iter().enumerate().take(lead_pawns).skip(1) {
+                index += indices.binomial[i][indices.map_pawns[*square] as usize];
+            }
+        } else {
+            // Mirror the board so the first piece is on the first four ranks
+            if rank(squares[0]) > 3 {
+                for square in &mut squares[..size] {
+                    *square ^= 56;
+                }
+            }
+
+            // Mirror along the a1-h8 diagonal so the first piece of the leading group that is
+            // not on the diagonal is below it
+            for i in 0..item.group_length[0] {
+                if off_diagonal(squares[i]) == 0 {
+                    continue;
+                }
+
+                if off_diagonal(squares[i]) > 0 {
+                    for square in &mut squares[i..size] {
+                        *square = ((*square >> 3) | (*square << 3)) & 63;
+                    }
+                }
+
+                break;
+            }
+
+            if self.has_unique_pieces {
+                let adjust_first = u64::from(squares[1] > squares[0]);
+                let adjust_second = u64::from(squares[2] > squares[0]) + u64::from(squares[2] > squares[1]);
+                let [first, second, third] = [squares[0] as u64, squares[1] as u64, squares[2] as u64];
+
+                index = if off_diagonal(squares[0]) != 0 {
+                    (indices.map_a1d1d4[squares[0]] * 63 + (second - adjust_first)) * 62 + third - adjust_second
+                } else if off_diagonal(squares[1]) != 0 {
+                    (6 * 63 + (first >> 3) * 28 + indices.map_b1h1h7[squares[1]]) * 62 + third - adjust_second
+                } else if off_diagonal(squares[2]) != 0 {
+                    6 * 63 * 62
+                        + 4 * 28 * 62
+                        + (first >> 3) * 7 * 28
+                        + ((second >> 3) - adjust_first) * 28
+                        + indices.map_b1h1h7[squares[2]]
+                } else {
+                    6 * 63 * 62
+                        + 4 * 28 * 62
+                        + 4 * 7 * 28
+                        + (first >> 3) * 7 * 6
+                        + ((second >> 3) - adjust_first) * 6
+                        + ((third >> 3) - adjust_second)
+                };
+            } else {
+                index = indices.map_kk[indices.map_a1d1d4[squares[0]] as usize][squares[1]];
+            }
+        }
+
+        index *= item.group_index[0];
+
+        // The other groups are encoded by the squares left for them, in ascending order
+        let mut start = item.group_length[0];
+        let mut remaining_pawns = self.has_pawns && self.pawn_count[1] > 0;
+        let mut group = 1;
+
+        while item.group_length[group] != 0 {
+            let length = item.group_length[group];
+            squares.get_mut(start..start + length)?.sort_unstable();
+
+            let mut group_index = 0;
+
+            for i in 0..length {
+                let square = squares[start + i];
+                let adjust = squares[..start].iter().filter(|other| square > **other).count();
+                let available = square.checked_sub(adjust + if remaining_pawns { 8 } else { 0 })?;
+
+                group_index += indices.binomial.get(i + 1)?[available];
+            }
+
+            remaining_pawns = false;
+            index += group_index * item.group_index[group];
+            start += length;
+            group += 1;
+        }
+
+        Some(Some((side_to_move % sides, lead_file, index)))
+    }
+
+    // Finds the value at an index in the Huffman coded blocks of recursively paired symbols
+    fn decompress(&self, item: &PairsData, index: u64) -> Option<u16> {
+        let bytes = &self.bytes;
+
+        if item.flags & SINGLE_VALUE != 0 {
+            return Some(u16::from(item.min_symbol_length));
+        }
+
+        // The sparse index points into the block lengths every span values
+        let k = usize::try_from(index / item.span).ok()?;
+
+        let mut block = u64::from(u32_le(bytes, item.sparse_index + k * 6)?);
+        let mut offset = i64::from(u16_le(bytes, item.sparse_index + k * 6 + 4)?);
+
+        offset += (index % item.span) as i64 - (item.span / 2) as i64;
+
+        let block_length = |block: u64| -> Option<i64> {
+            if block >= item.block_length_size {
+                return None;
+            }
+
+            Some(i64::from(u16_le(bytes, item.block_lengths + block as usize * 2)?))
+        };
+
+        while offset < 0 {
+            block = block.checked_sub(1)?;
+            offset += block_length(block)? + 1;
+        }
+
+        while offset > block_length(block)? {
+            offset -= block_length(block)? + 1;
+            block += 1;
+        }
+
+        let mut pointer = item.data + usize::try_from(block * item.block_size).ok()?;
+        let mut buffer = u64_be(bytes, pointer)?;
+        let mut buffer_size = 64;
+        pointer += 8;
+
+        let mut symbol;
+
+        loop {
+            let mut length = 0;
+
+            while buffer < *item.base.get(length)? {
+                length += 1;
+            }
+
+            let shift = 64 - length as u32 - u32::from(item.min_symbol_length);
+            symbol = ((buffer - item.base[length]).checked_shr(shift).unwrap_or(0) as u16)
+                .wrapping_add(u16_le(bytes, item.lowest_symbols + length * 2)?) as usize;
+
+            let symbol_length = i64::from(*item.symbol_lengths.get(symbol)?);
+
+            if offset < symbol_length + 1 {
+                break;
+            }
+
+            offset -= symbol_length + 1;
+            length += usize::from(item.min_symbol_length);
+            buffer = buffer.checked_shl(length as u32).unwrap_or(0);
+            buffer_size -= length as i32;
+
+            if buffer_size <= 32 {
+                buffer_size += 32;
+                buffer |= u64::from(u32_be(bytes, pointer)?) << (64 - buffer_size);
+                pointer += 4;
+            }
+        }
+
+        // The symbol expands to several values, follow the pairs down to the one at the offset
+        while *item.symbol_lengths.get(symbol)? != 0 {
+            let left = tree_left(bytes, item.tree, symbol)?;
+            let left_length = i64::from(*item.symbol_lengths.get(left)?);
+
+            if offset < left_length + 1 {
+                symbol = left;
+            } else {
+                offset -= left_length + 1;
+                symbol = tree_right(bytes, item.tree, symbol)?;
+            }
+        }
+
+        Some(tree_left(bytes, item.tree, symbol)? as u16)
+    }
+
+    // Turns a stored DTZ value into plies
+    fn map_score(&self, file: usize, mut value: i32, wdl: i32) -> Option<i32> {
+        // The maps are stored in the order win, loss, cursed win and blessed loss
+        const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+
+        let item = &self.items[file][0];
+        let map_index = item.map_index[*WDL_MAP.get(usize::try_from(wdl + 2).ok()?)?] as usize;
+        let value_index = usize::try_from(value).ok()?;
+
+        if item.flags & MAPPED != 0 {
+            value = if item.flags & WIDE != 0 {
+                i32::from(u16_le(&self.bytes, self.map + 2 * (map_index + value_index))?)
+            } else {
+                i32::from(byte(&self.bytes, self.map + map_index + value_index)?)
+            };
+        }
+
+        // Tables store moves instead of plies unless the flags say otherwise
+        if (wdl == 2 && item.flags & WIN_PLIES == 0) || (wdl == -2 && item.flags & LOSS_PLIES == 0) || wdl.abs() == 1 {
+            value *= 2;
+        }
+
+        Some(value + 1)
+    }
+}
+
+// Returns the number of values a symbol expands to minus one
+fn symbol_length(bytes: &[u8], tree: usize, symbol: usize, lengths: &mut [u8], visited: &mut [bool]) -> Option<u8> {
+    *visited.get_mut(symbol)? = true;
+
+    let right = tree_right(bytes, tree, symbol)?;
+
+    if right == 0xfff {
+        return Some(0);
+    }
+
+    let left = tree_left(bytes, tree, symbol)?;
+
+    for child in [left, right] {
+        if !*visited.get(child)? {
+            lengths[child] = symbol_length(bytes, tree, child, lengths, visited)?;
+        }
+    }
+
+    Some(lengths[left].wrapping_add(lengths[right]).wrapping_add(1))
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Writes a WDL table where every side of every file stores one bit per position, 1 for a win
+    /// and 0 for a loss, chosen by `value` from the index
+    pub(in crate::tablebase) fn synthetic_table(material: &Material, value: impl Fn(u64) -> bool) -> Vec<u8> {
+        let white_pawns = material.count(PAWN);
+        let black_pawns = material.count(PAWN | 8);
+
+        // The pawns of the leading color come first, then the other pawns
+        let lead = if black_pawns == 0 || (white_pawns > 0 && black_pawns >= white_pawns) { 0 } else { 8 };
+        let mut pieces: Vec<u8> = material.pieces.concat();
+        pieces.sort_by_key(|piece| (*piece != PAWN | lead, *piece & 7 != PAWN));
+        let symmetric = material.pieces[0] == material.pieces[1].iter().map(|piece| piece ^ 8).collect::<Vec<u8>>();
+        let pawns_on_both_sides = white_pawns > 0 && black_pawns > 0;
+        let files = if white_pawns + black_pawns > 0 { 4 } else { 1 };
+        let sides = if symmetric { 1 } else { 2 };
+
+        let mut bytes = WDL_MAGIC.to_vec();
+        bytes.push(if symmetric { 0 } else { SPLIT } | if files == 4 { HAS_PAWNS } else { 0 });
+
+        for _ in 0..files {
+            bytes.push(0);
+            if pawns_on_both_sides {
+                bytes.push(0x11);
+            }
+
+            for piece in &pieces {
+                bytes.push(piece | piece << 4);
+            }
+        }
+
+        if bytes.len() % 2 == 1 {
+            bytes.push(0);
+        }
+
+        // One block of 2^13 bytes holds all values, one bit each with a symbol for each result
+        for _ in 0..files * sides {
+            bytes.extend([0, 13, 16, 0]);
+            bytes.extend(1u32.to_le_bytes());
+            bytes.extend([1, 1]);
+            bytes.extend(0u16.to_le_bytes());
+            bytes.extend(2u16.to_le_bytes());
+            bytes.extend([0, 0xf0, 0xff, 4, 0xf0, 0xff]);
+        }
+
+        for _ in 0..files * sides {
+            bytes.extend(0u32.to_le_bytes());
+            bytes.extend(32768u16.to_le_bytes());
+        }
+
+        for _ in 0..files * sides {
+            bytes.extend(u16::MAX.to_le_bytes());
+        }
+
+        for _ in 0..files * sides {
+            while !bytes.len().is_multiple_of(64) {
+                bytes.push(0);
+            }
+
+            let mut block = vec![0u8; 1 << 13];
+
+            for index in 0..(1 << 16) {
+                if value(index) {
+                    block[(index / 8) as usize] |= 0x80 >> (index % 8);
+                }
+            }
+
+            bytes.extend(block);
+        }
+
+        bytes
+    }
+
+    // Every position of the material with the pieces on different squares
+    fn placements(pieces: usize, pawn: Option<usize>) -> Vec<Vec<usize>> {
+        let mut placements = vec![Vec::new()];
+
+        for piece in 0..pieces {
+            let mut next = Vec::new();
+
+            for placement in placements {
+                for square in 0..64 {
+                    if !placement.contains(&square) && (Some(piece) != pawn || (8..56).contains(&square)) {
+                        let mut placement = placement.clone();
+                        placement.push(square);
+                        next.push(placement);
+                    }
+                }
+            }
+
+            placements = next;
+        }
+
+        placements
+    }
+
+    #[test]
+    fn index_tables() {
+        let indices = indices();
+
+        assert_eq!(indices.map_pawns[8], 47);
+        assert_eq!(indices.map_pawns[15], 46);
+        assert_eq!(indices.map_pawns[52], 0);
+        assert_eq!(indices.lead_pawns_size[1], [6, 6, 6, 6]);
+        assert_eq!(indices.binomial[2][10], 45);
+        assert_eq!(indices.map_a1d1d4[1], 0);
+        assert_eq!(indices.map_a1d1d4[27], 9);
+        assert_eq!(indices.map_kk.iter().flatten().max(), Some(&461));
+    }
+
+    #[test]
+    fn positions_have_one_index_for_each_symmetry() {
+        let material = Material::parse("KQvK").unwrap();
+        let table = Table::new(synthetic_table(&material, |_| false), &material, Kind::Wdl).unwrap();
+
+        let symmetries: [fn(usize) -> usize; 8] = [
+            |square| square,
+            |square| square ^ 7,
+            |square| square ^ 56,
+            |square| square ^ 63,
+            |square| (square >> 3) | (square & 7) << 3,
+            |square| ((square >> 3) | (square & 7) << 3) ^ 7,
+            |square| ((square >> 3) | (square & 7) << 3) ^ 56,
+            |square| ((square >> 3) | (square & 7) << 3) ^ 63,
+        ];
+
+        let mut classes = HashMap::new();
+
+        for squares in placements(3, None) {
+            let pieces = [(squares[0], 6), (squares[1], 5), (squares[2], 14)];
+            let (_, _, index) = table.encode(&pieces, false, false).unwrap().unwrap();
+
+            assert!(index < 31332);
+
+            let class = symmetries
+                .iter()
+                .map(|symmetry| squares.iter().map(|square| symmetry(*square)).collect::<Vec<usize>>())
+                .min()
+                .unwrap();
+
+            assert_eq!(classes.entry(index).or_insert_with(|| class.clone()), &class);
+        }
+    }
+
+    #[test]
+    fn pawn_positions_have_one_index_for_each_symmetry() {
+        let material = Material::parse("KPvK").unwrap();
+        let table = Table::new(synthetic_table(&material, |_| false), &material, Kind::Wdl).unwrap();
+
+        let mut classes = HashMap::new();
+
+        for squares in placements(3, Some(1)) {
+            let pieces = [(squares[0], 6), (squares[1], 1), (squares[2], 14)];
+            let (_, file, index) = table.encode(&pieces, false, false).unwrap().unwrap();
+
+            assert!(index < table.items[file][0].group_index[3]);
+
+            let class = squares.clone().min(squares.iter().map(|square| square ^ 7).collect());
+
+            assert_eq!(classes.entry((file, index)).or_insert_with(|| class.clone()), &class);
+        }
+    }
+}
@@ -73,3 +73,105 @@
 //         ));
 //     }
 // }
+
+#[cfg(test)]
+mod rules {
+    use crate::prelude::*;
+
+    fn from_fen(fen: &str) -> ChessGame {
+        ChessGame::from_fen(String::from(fen)).unwrap()
+    }
+
+    #[test]
+    fn kings_can_not_stand_next_to_each_other() {
+        let game = from_fen("8/8/4k3/8/4K3/8/8/8 w - - 0 1");
+        let valid_moves = game.get_valid_moves(&BoardPosition::from((E, Four)));
+
+        for file in [D, E, F] {
+            assert!(valid_moves.get(&BoardPosition::from((file, Five))).is_none());
+        }
+        assert!(valid_moves.get(&BoardPosition::from((E, Three))).is_some());
+    }
+
+    #[test]
+    fn castling_needs_the_rook_in_its_corner() {
+        // The rook on h1 was captured without the castling rights being removed
+        let game = from_fen("4k3/8/8/8/8/8/8/R3K3 w KQ - 0 1");
+        let valid_moves = game.get_valid_moves(&BoardPosition::from((E, One)));
+
+        assert!(valid_moves.get(&BoardPosition::from((G, One))).is_none());
+
+        let game = from_fen("r3k3/8/8/8/8/8/8/4K3 b kq - 0 1");
+        let valid_moves = game.get_valid_moves(&BoardPosition::from((E, Eight)));
+
+        assert!(valid_moves.get(&BoardPosition::from((G, Eight))).is_none());
+    }
+
+    #[test]
+    fn black_can_not_castle_queen_side_through_a_piece() {
+        let game = from_fen("r2qk3/8/8/8/8/8/8/4K3 b q - 0 1");
+        let valid_moves = game.get_valid_moves(&BoardPosition::from((E, Eight)));
+
+        assert!(valid_moves.get(&BoardPosition::from((B, Eight))).is_none());
+        assert!(valid_moves.get(&BoardPosition::from((C, Eight))).is_none());
+    }
+
+    #[test]
+    fn black_pawns_capture_towards_both_sides() {
+        let game = from_fen("4k3/8/8/3p4/2N1N3/8/8/4K3 b - - 0 1");
+        let valid_moves = game.get_valid_moves(&BoardPosition::from((D, Five)));
+
+        assert!(matches!(
+            valid_moves.get(&BoardPosition::from((C, Four))),
+            Some(MoveType::Capture)
+        ));
+        assert!(matches!(
+            valid_moves.get(&BoardPosition::from((E, Four))),
+            Some(MoveType::Capture)
+        ));
+        assert!(valid_moves.get(&BoardPosition::from((E, Six))).is_none());
+    }
+
+    #[test]
+    fn pawns_move_two_squares_only_from_their_first_square() {
+        let mut game = ChessGame::default();
+
+        game.make_move(&"e2e3".parse().unwrap()).unwrap();
+        game.make_move(&"e7e6".parse().unwrap()).unwrap();
+
+        let valid_moves = game.get_valid_moves(&BoardPosition::from((E, Three)));
+
+        assert!(valid_moves.get(&BoardPosition::from((E, Four))).is_some());
+        assert!(valid_moves.get(&BoardPosition::from((E, Five))).is_none());
+    }
+
+    #[test]
+    fn queen_side_castling() {
+        let mut game = from_fen("r3k3/8/8/8/8/8/8/R3K3 w Qq - 0 1");
+
+        game.make_move(&"e1c1".parse().unwrap()).unwrap();
+
+        assert_eq!(game.to_fen(), "r3k3/8/8/8/8/8/8/2KR4 b q - 1 1");
+
+        let mut game = from_fen("r3k3/8/8/8/8/8/8/4K3 b q - 0 1");
+
+        game.make_move(&"e8c8".parse().unwrap()).unwrap();
+
+        assert_eq!(game.to_fen(), "2kr4/8/8/8/8/8/8/4K3 w - - 1 2");
+    }
+
+    #[test]
+    fn kings_can_not_castle_out_of_check() {
+        let game = from_fen("r3k3/8/8/8/8/8/8/R3K2r w Qq - 0 1");
+
+        assert!(!game.get_legal_moves().contains(&"e1c1".parse().unwrap()));
+
+        let game = from_fen("4r1k1/8/8/8/8/8/8/4K2R w K - 0 1");
+
+        assert!(!game.get_legal_moves().contains(&"e1g1".parse().unwrap()));
+
+        let game = from_fen("4k2r/8/8/8/8/8/8/4R1K1 b k - 0 1");
+
+        assert!(!game.get_legal_moves().contains(&"e8g8".parse().unwrap()));
+    }
+}
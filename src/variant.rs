@@ -0,0 +1,231 @@
+mod antichess;
+mod atomic;
+mod crazyhouse;
+mod horde;
+mod king_of_the_hill;
+mod racing_kings;
+mod three_check;
+
+use std::any::Any;
+
+use crate::board::{Board, GameState, MoveType, Termination, Turn};
+use crate::piece::{Color, Piece};
+use crate::position::BoardPosition;
+use crate::ChessGame;
+
+pub use antichess::Antichess;
+pub use atomic::Atomic;
+pub use crazyhouse::Crazyhouse;
+pub use horde::Horde;
+pub use king_of_the_hill::KingOfTheHill;
+pub use racing_kings::RacingKings;
+pub use three_check::ThreeCheck;
+
+/// The rules of a game of chess
+///
+/// Every hook has a default implementation following the rules of standard chess, a variant only
+/// has to override the hooks where its rules differ. The standard rules can be reused from an
+/// overriding hook through [`Standard`], for example `Standard.game_state(game)`.
+///
+/// ```rust
+/// use viktoe_chess::board::{GameState, Termination};
+/// use viktoe_chess::prelude::*;
+/// use viktoe_chess::variant::{Standard, Variant};
+///
+/// struct NoFiftyMoveRule;
+///
+/// impl Variant for NoFiftyMoveRule {
+///     fn name(&self) -> &'static str {
+///         "no fifty move rule"
+///     }
+///
+///     fn game_state(&self, game: &ChessGame) -> GameState {
+///         match Standard.game_state(game) {
+///             GameState::Draw(Termination::FiftyMoveRule) => GameState::Ongoing,
+///             state => state,
+///         }
+///     }
+/// }
+///
+/// let game = ChessGame::new(NoFiftyMoveRule);
+///
+/// assert_eq!(game.get_variant().name(), "no fifty move rule");
+/// ```
+pub trait Variant: Any + Send + Sync {
+    /// The name of the variant
+    fn name(&self) -> &'static str;
+
+    /// Returns the board at the start of the game
+    fn start_position(&self) -> Board<Color<Piece>> {
+        Board::standard_position()
+    }
+
+    /// Returns false if the move that resulted in `game` is not allowed for `player_color`.
+    ///
+    /// Called with the game as it is directly after the move has been made, before the turn is
+    /// passed on to the other player.
+    fn is_legal(&self, game: &ChessGame, player_color: &Turn) -> bool {
+        !game.is_in_check(player_color)
+    }
+
+    /// Returns the moves from `position` the player whos turn it is is allowed to make.
+    ///
+    /// Called with the legal moves from the square, the moves from every square can be found
+    /// with [`ChessGame::get_unfiltered_moves`].
+    fn filter_moves(
+        &self,
+        _game: &ChessGame,
+        _position: &BoardPosition,
+        moves: Board<MoveType>,
+    ) -> Board<MoveType> {
+        moves
+    }
+
+    /// Returns false if the kings can not castle
+    fn allows_castling(&self) -> bool {
+        true
+    }
+
+    /// Called after a piece has been moved, before the turn is passed on to the other player.
+    ///
+    /// `captured` contains the piece that was captured by the move if there was one.
+    fn after_move(
+        &self,
+        _game: &mut ChessGame,
+        _initial_position: &BoardPosition,
+        _desired_position: &BoardPosition,
+        _move_type: &MoveType,
+        _captured: &Option<Color<Piece>>,
+    ) {
+    }
+
+    /// The number of checks a player has to give to win, included in FEN strings if it is set
+    fn check_limit(&self) -> Option<u8> {
+        None
+    }
+
+    /// The pieces a pawn can be promoted to
+    fn promotion_pieces(&self) -> Vec<Piece> {
+        vec![Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight]
+    }
+
+    /// Returns true if captured pieces are kept in pockets, included in FEN strings if it is set
+    fn has_pockets(&self) -> bool {
+        false
+    }
+
+    /// Returns false if captures do not just win the captured piece or are compulsory, the
+    /// search then does not prune captures that lose material in the exchange
+    fn has_standard_captures(&self) -> bool {
+        true
+    }
+
+    /// Returns the state of the game for the player whos turn it is
+    fn game_state(&self, game: &ChessGame) -> GameState {
+        let turn = game.get_player_turn();
+
+        if game.is_in_check(turn) {
+            // If the player whos turn it is can move any piece they are not in mate
+            if game.has_valid_moves() {
+                GameState::Check
+            } else {
+                GameState::CheckMate
+            }
+        } else if game.half_move >= 100 {
+            GameState::Draw(Termination::FiftyMoveRule)
+        } else {
+            GameState::Ongoing
+        }
+    }
+}
+
+impl dyn Variant {
+    // Returns true if the rules are the rules of standard chess, used where results are only
+    // known for standard chess
+    pub(crate) fn is_standard(&self) -> bool {
+        (self as &dyn Any).is::<Standard>()
+    }
+}
+
+/// The rules of standard chess
+pub struct Standard;
+
+impl Variant for Standard {
+    fn name(&self) -> &'static str {
+        "standard"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::{File::*, Rank::*};
+
+    struct NoFiftyMoveRule;
+
+    impl Variant for NoFiftyMoveRule {
+        fn name(&self) -> &'static str {
+            "no fifty move rule"
+        }
+
+        fn game_state(&self, game: &ChessGame) -> GameState {
+            match Standard.game_state(game) {
+                GameState::Draw(Termination::FiftyMoveRule) => GameState::Ongoing,
+                state => state,
+            }
+        }
+    }
+
+    #[test]
+    fn custom_variant_overrides_game_end() {
+        let mut game = ChessGame::new(NoFiftyMoveRule);
+
+        for _ in 0..26 {
+            game.move_piece(&(G, One).into(), &(F, Three).into()).unwrap();
+            game.move_piece(&(G, Eight).into(), &(F, Six).into()).unwrap();
+            game.move_piece(&(F, Three).into(), &(G, One).into()).unwrap();
+            game.move_piece(&(F, Six).into(), &(G, Eight).into()).unwrap();
+        }
+
+        assert!(matches!(game.get_game_state(), GameState::Ongoing));
+        assert_eq!(game.get_variant().name(), "no fifty move rule");
+        assert!(game.get_variant().has_standard_captures());
+        assert!(!Atomic.has_standard_captures());
+        assert!(!Antichess.has_standard_captures());
+        assert!(!Crazyhouse.has_standard_captures());
+    }
+
+    #[test]
+    fn king_cannot_move_into_check() {
+        let mut game = ChessGame::default();
+
+        game.move_piece(&(E, Two).into(), &(E, Four).into()).unwrap();
+        game.move_piece(&(E, Seven).into(), &(E, Five).into()).unwrap();
+        game.move_piece(&(D, One).into(), &(G, Four).into()).unwrap();
+        game.move_piece(&(D, Seven).into(), &(D, Six).into()).unwrap();
+        game.move_piece(&(A, Two).into(), &(A, Three).into()).unwrap();
+
+        // d7 is on the diagonal of the queen
+        let moves = game.get_valid_moves(&(E, Eight).into());
+
+        assert!(moves.get(&(D, Seven).into()).is_none());
+        assert!(moves.get(&(E, Seven).into()).is_some());
+    }
+
+    #[test]
+    fn scholars_mate_is_check_mate() {
+        let mut game = ChessGame::default();
+
+        game.move_piece(&(E, Two).into(), &(E, Four).into()).unwrap();
+        game.move_piece(&(E, Seven).into(), &(E, Five).into()).unwrap();
+        game.move_piece(&(D, One).into(), &(H, Five).into()).unwrap();
+        game.move_piece(&(B, Eight).into(), &(C, Six).into()).unwrap();
+        game.move_piece(&(F, One).into(), &(C, Four).into()).unwrap();
+        game.move_piece(&(G, Eight).into(), &(F, Six).into()).unwrap();
+
+        assert!(matches!(
+            game.move_piece(&(H, Five).into(), &(F, Seven).into()),
+            Ok(GameState::CheckMate)
+        ));
+    }
+}
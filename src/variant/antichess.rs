@@ -0,0 +1,187 @@
+use crate::board::check::get_attackers;
+use crate::board::{Board, GameState, MoveType, Termination, Turn};
+use crate::piece::shorthands::*;
+use crate::piece::{Color, Piece};
+use crate::position::{self, BoardPosition, File::*, Rank::*};
+use crate::variant::Variant;
+use crate::ChessGame;
+
+/// Chess where captures are compulsory and a player wins by losing all their pieces or by having
+/// no moves. The king is an ordinary piece that can be captured, there is no check and no
+/// castling, and pawns can also be promoted to kings.
+pub struct Antichess;
+
+impl Antichess {
+    // Returns true if any piece of the player whos turn it is can capture, found from the attacks
+    // on the pieces of the opponent instead of generating every move
+    fn can_capture(game: &ChessGame) -> bool {
+        let turn = game.get_player_turn();
+
+        let can_capture = position::iter().into_iter().any(|position| {
+            matches!(game.get_square(&position), Some(piece) if !piece.same_color(turn))
+                && !get_attackers(&game.board, &position, turn).is_empty()
+        });
+
+        // A pawn next to a pawn that just moved two squares can take it by en passant
+        can_capture
+            || game.en_passant.iter().any(|position| {
+                [(-1, 0), (1, 0)].into_iter().any(|vector| {
+                    matches!(position.add(vector).map(|position| game.get_square(&position).clone()),
+                        Ok(Some(piece)) if piece.same_color(turn) && matches!(piece.get_internal(), Piece::Pawn { .. }))
+                })
+            })
+    }
+}
+
+impl Variant for Antichess {
+    fn name(&self) -> &'static str {
+        "antichess"
+    }
+
+    fn start_position(&self) -> Board<Color<Piece>> {
+        let mut board = Board::standard_position();
+
+        // The kings are ordinary pieces and can not castle
+        board.set(&BoardPosition::from((E, One)), Some(WHITE_KING));
+        board.set(&BoardPosition::from((E, Eight)), Some(BLACK_KING));
+
+        board
+    }
+
+    fn is_legal(&self, _game: &ChessGame, _player_color: &Turn) -> bool {
+        true
+    }
+
+    fn filter_moves(
+        &self,
+        game: &ChessGame,
+        _position: &BoardPosition,
+        mut moves: Board<MoveType>,
+    ) -> Board<MoveType> {
+        // Only squares with moves other than captures have to look for captures of other pieces
+        let has_other_moves = moves
+            .iter()
+            .any(|move_type| matches!(move_type, Some(MoveType::Move | MoveType::Drop)));
+
+        if has_other_moves && Antichess::can_capture(game) {
+            for position in position::iter() {
+                if !matches!(moves.get(&position), Some(MoveType::Capture)) {
+                    moves.set(&position, None);
+                }
+            }
+        }
+
+        moves
+    }
+
+    fn allows_castling(&self) -> bool {
+        false
+    }
+
+    fn has_standard_captures(&self) -> bool {
+        false
+    }
+
+    fn promotion_pieces(&self) -> Vec<Piece> {
+        vec![
+            Piece::Queen,
+            Piece::Rook,
+            Piece::Bishop,
+            Piece::Knight,
+            Piece::King {
+                check_state: None,
+                castling_state: (false, false),
+            },
+        ]
+    }
+
+    fn game_state(&self, game: &ChessGame) -> GameState {
+        let turn = game.get_player_turn();
+
+        if !game.iter().any(|piece| matches!(piece, Some(piece) if piece.same_color(turn))) {
+            GameState::Win(turn.clone(), Termination::AllPiecesLost)
+        } else if !Antichess::can_capture(game) && !game.has_valid_moves() {
+            GameState::Win(turn.clone(), Termination::Stalemate)
+        } else if game.half_move >= 100 {
+            GameState::Draw(Termination::FiftyMoveRule)
+        } else {
+            GameState::Ongoing
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::GameResult;
+    use crate::chess_move::ChessMove;
+
+    fn game(fen: &str) -> ChessGame {
+        ChessGame::from_fen_with_variant(String::from(fen), Antichess).unwrap()
+    }
+
+    #[test]
+    fn captures_are_compulsory() {
+        let mut game = ChessGame::new(Antichess);
+
+        game.make_move(&"e2e3".parse().unwrap()).unwrap();
+        game.make_move(&"b7b5".parse().unwrap()).unwrap();
+
+        assert_eq!(game.get_legal_moves(), vec!["f1b5".parse::<ChessMove>().unwrap()]);
+        assert!(game.get_valid_moves(&(A, Two).into()).iter().all(|square| square.is_none()));
+    }
+
+    #[test]
+    fn en_passant_is_compulsory() {
+        let game = game("8/8/8/3pP3/8/8/8/k7 w - d6 0 1");
+
+        assert_eq!(game.get_legal_moves(), vec!["e5d6".parse::<ChessMove>().unwrap()]);
+    }
+
+    #[test]
+    fn king_is_an_ordinary_piece() {
+        let game = game("8/8/8/8/8/8/r7/4K3 w - - 0 1");
+
+        assert!(game.get_valid_moves(&(E, One).into()).get(&(E, Two).into()).is_some());
+        assert_eq!(ChessGame::new(Antichess).to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1");
+    }
+
+    #[test]
+    fn promotion_to_king() {
+        let mut game = game("8/P7/8/8/8/8/8/k7 w - - 0 1");
+
+        game.make_move(&"a7a8k".parse().unwrap()).unwrap();
+
+        assert_eq!(game.to_fen(), "K7/8/8/8/8/8/8/k7 b - - 0 1");
+    }
+
+    #[test]
+    fn losing_all_pieces_wins() {
+        let mut game = game("8/8/8/8/8/8/8/Rr6 w - - 0 1");
+
+        game.make_move(&"a1b1".parse().unwrap()).unwrap();
+
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::Black),
+                termination: Termination::AllPiecesLost,
+            })
+        );
+    }
+
+    #[test]
+    fn stalemate_wins() {
+        let mut game = game("8/8/8/8/p7/8/P7/8 w - - 0 1");
+
+        game.make_move(&"a2a3".parse().unwrap()).unwrap();
+
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::Black),
+                termination: Termination::Stalemate,
+            })
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use crate::board::{GameState, MoveType, Termination, Turn};
+use crate::piece::shorthands::KING_MOVES;
+use crate::piece::{Color, Piece};
+use crate::position::BoardPosition;
+use crate::variant::Variant;
+use crate::ChessGame;
+
+/// Chess where every capture causes an explosion that removes the capturing piece and every piece
+/// that is not a pawn around the captured square. A player wins by exploding the other king.
+///
+/// Kings can not capture and may stand next to each other, a king next to the other king can not
+/// be in check since capturing it would explode both kings.
+pub struct Atomic;
+
+impl Atomic {
+    // Returns true if the king of the player is still on the board
+    fn has_king(game: &ChessGame, player_color: &Turn) -> bool {
+        game.get_king_position(player_color).is_some()
+    }
+
+    fn kings_are_adjacent(game: &ChessGame) -> bool {
+        let (white_king, black_king) = match (
+            game.get_king_position(&Turn::White),
+            game.get_king_position(&Turn::Black),
+        ) {
+            (Some(white_king), Some(black_king)) => (white_king, black_king),
+            _ => return false,
+        };
+
+        let (white_file, white_rank): (u8, u8) = white_king.into();
+        let (black_file, black_rank): (u8, u8) = black_king.into();
+
+        white_file.abs_diff(black_file) <= 1 && white_rank.abs_diff(black_rank) <= 1
+    }
+
+    /// Returns true if the king of the player is in check under the rules of atomic chess
+    pub fn is_in_check(game: &ChessGame, player_color: &Turn) -> bool {
+        !Atomic::kings_are_adjacent(game) && game.is_in_check(player_color)
+    }
+}
+
+impl Variant for Atomic {
+    fn name(&self) -> &'static str {
+        "atomic"
+    }
+
+    fn is_legal(&self, game: &ChessGame, player_color: &Turn) -> bool {
+        if !Atomic::has_king(game, player_color) {
+            return false;
+        }
+
+        // Exploding the other king ends the game even if the own king is left in check
+        if !Atomic::has_king(game, &player_color.opponent()) {
+            return true;
+        }
+
+        !Atomic::is_in_check(game, player_color)
+    }
+
+    fn has_standard_captures(&self) -> bool {
+        false
+    }
+
+    fn after_move(
+        &self,
+        game: &mut ChessGame,
+        _initial_position: &BoardPosition,
+        desired_position: &BoardPosition,
+        _move_type: &MoveType,
+        captured: &Option<Color<Piece>>,
+    ) {
+        if captured.is_none() {
+            return;
+        }
+
+        // The capturing piece always explodes, surrounding pawns survive
+        game.board.set(desired_position, None);
+
+        for vector in KING_MOVES {
+            if let Ok(position) = desired_position.add(vector) {
+                if !matches!(
+                    game.board.get(&position),
+                    Some(Color::White(Piece::Pawn { .. }) | Color::Black(Piece::Pawn { .. }))
+                ) {
+                    game.board.set(&position, None);
+                }
+            }
+        }
+    }
+
+    fn game_state(&self, game: &ChessGame) -> GameState {
+        let turn = game.get_player_turn();
+
+        if !Atomic::has_king(game, turn) {
+            return GameState::Win(turn.opponent(), Termination::Explosion);
+        }
+
+        if Atomic::is_in_check(game, turn) {
+            if game.has_valid_moves() {
+                GameState::Check
+            } else {
+                GameState::CheckMate
+            }
+        } else if game.half_move >= 100 {
+            GameState::Draw(Termination::FiftyMoveRule)
+        } else {
+            GameState::Ongoing
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::GameResult;
+    use crate::position::{File::*, Rank::*};
+
+    fn game(fen: &str) -> ChessGame {
+        ChessGame::from_fen_with_variant(String::from(fen), Atomic).unwrap()
+    }
+
+    #[test]
+    fn capture_explodes_surrounding_pieces() {
+        let mut game = game("4k3/8/2nrb3/3p4/2P5/8/8/4K3 w - - 0 1");
+
+        game.move_piece(&(C, Four).into(), &(D, Five).into()).unwrap();
+
+        assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn pawns_survive_explosions() {
+        let mut game = game("4k3/8/2p5/3r4/8/8/8/3RK3 w - - 0 1");
+
+        game.move_piece(&(D, One).into(), &(D, Five).into()).unwrap();
+
+        assert_eq!(game.to_fen(), "4k3/8/2p5/8/8/8/8/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn exploding_the_king_wins() {
+        let mut game = game("3k4/3r4/8/8/8/8/8/3RK3 w - - 0 1");
+
+        game.move_piece(&(D, One).into(), &(D, Seven).into()).unwrap();
+
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::White),
+                termination: Termination::Explosion,
+            })
+        );
+    }
+
+    #[test]
+    fn king_can_not_capture() {
+        let game = game("4k3/8/8/8/8/8/4p3/4K3 w - - 0 1");
+
+        assert!(game.get_valid_moves(&(E, One).into()).get(&(E, Two).into()).is_none());
+    }
+
+    #[test]
+    fn can_not_explode_own_king() {
+        let game = game("4k3/8/8/8/8/8/3p4/3QK3 w - - 0 1");
+
+        assert!(game.get_valid_moves(&(D, One).into()).get(&(D, Two).into()).is_none());
+    }
+
+    #[test]
+    fn kings_may_touch() {
+        let fen = "8/8/8/3k4/8/3K4/8/8 w - - 0 1";
+
+        assert!(game(fen).get_valid_moves(&(D, Three).into()).get(&(D, Four).into()).is_some());
+        assert!(ChessGame::from_fen(String::from(fen))
+            .unwrap()
+            .get_valid_moves(&(D, Three).into())
+            .get(&(D, Four).into())
+            .is_none());
+    }
+}
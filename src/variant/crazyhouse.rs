@@ -0,0 +1,136 @@
+use crate::board::MoveType;
+use crate::piece::{Color, PawnState, Piece};
+use crate::position::BoardPosition;
+use crate::variant::Variant;
+use crate::ChessGame;
+
+/// Standard chess where captured pieces are added to the pocket of the capturing player, who can
+/// drop them on any empty square instead of moving. Pawns can not be dropped on the first or last
+/// rank and promoted pieces become pawns again when captured.
+pub struct Crazyhouse;
+
+impl Variant for Crazyhouse {
+    fn name(&self) -> &'static str {
+        "crazyhouse"
+    }
+
+    fn has_pockets(&self) -> bool {
+        true
+    }
+
+    fn has_standard_captures(&self) -> bool {
+        false
+    }
+
+    fn after_move(
+        &self,
+        game: &mut ChessGame,
+        _initial_position: &BoardPosition,
+        desired_position: &BoardPosition,
+        _move_type: &MoveType,
+        captured: &Option<Color<Piece>>,
+    ) {
+        let captured = match captured {
+            Some(piece) => piece.get_internal(),
+            None => return,
+        };
+
+        let piece = if game.is_promoted(desired_position) {
+            Piece::Pawn {
+                state: PawnState::Default,
+            }
+        } else {
+            match captured {
+                Piece::Pawn { .. } => Piece::Pawn {
+                    state: PawnState::Default,
+                },
+                piece => piece.clone(),
+            }
+        };
+
+        let turn = game.get_player_turn().clone();
+        game.add_to_pocket(&turn, piece);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{GameState, Turn};
+    use crate::chess_move::ChessMove;
+    use crate::position::{File::*, Rank::*};
+
+    fn game(fen: &str) -> ChessGame {
+        ChessGame::from_fen_with_variant(String::from(fen), Crazyhouse).unwrap()
+    }
+
+    #[test]
+    fn captured_pieces_go_to_pocket() {
+        let mut game = ChessGame::new(Crazyhouse);
+
+        for chess_move in ["e2e4", "d7d5", "e4d5", "d8d5"] {
+            game.make_move(&chess_move.parse().unwrap()).unwrap();
+        }
+
+        assert_eq!(game.get_pocket(&Turn::White), [Piece::Pawn { state: PawnState::Default }]);
+        assert_eq!(game.get_pocket(&Turn::Black), [Piece::Pawn { state: PawnState::Default }]);
+        assert_eq!(
+            game.to_fen(),
+            "rnb1kbnr/ppp1pppp/8/3q4/8/8/PPPP1PPP/RNBQKBNR[Pp] w KQkq - 0 3"
+        );
+    }
+
+    #[test]
+    fn drop_pieces() {
+        let mut game = game("4k3/8/8/8/8/8/8/4K3[Nq] w - - 0 1");
+
+        assert!(game.drop_piece(Piece::Queen, &(D, Four).into()).is_err());
+
+        game.make_move(&"N@f6".parse().unwrap()).unwrap();
+
+        assert!(matches!(game.get_game_state(), GameState::Check));
+        assert!(game.get_pocket(&Turn::White).is_empty());
+        assert_eq!(game.to_fen(), "4k3/8/5N2/8/8/8/8/4K3[q] b - - 1 1");
+    }
+
+    #[test]
+    fn pawns_can_not_be_dropped_on_last_rank() {
+        let game = game("4k3/8/8/8/8/8/8/4K3[P] w - - 0 1");
+
+        let drops = game.get_valid_drops(&Piece::Pawn { state: PawnState::Default });
+
+        assert!(drops.get(&(A, One).into()).is_none());
+        assert!(drops.get(&(A, Eight).into()).is_none());
+        assert!(drops.get(&(A, Two).into()).is_some());
+        assert!(drops.get(&(E, One).into()).is_none());
+    }
+
+    #[test]
+    fn drop_can_block_check() {
+        let game = game("4k3/8/8/8/8/8/8/r3K3[R] w - - 0 1");
+
+        let drops = game.get_valid_drops(&Piece::Rook);
+
+        assert!(drops.get(&(D, One).into()).is_some());
+        assert!(drops.get(&(D, Two).into()).is_none());
+        assert!(game.get_legal_moves().contains(&ChessMove::Drop {
+            piece: Piece::Rook,
+            to: (B, One).into(),
+        }));
+    }
+
+    #[test]
+    fn promoted_pieces_revert_to_pawns() {
+        let mut game = game("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+
+        game.make_move(&"a7b8q".parse().unwrap()).unwrap();
+
+        assert_eq!(game.to_fen(), "1Q~2k3/8/8/8/8/8/8/4K3[R] b - - 0 1");
+
+        let mut game = crate::variant::crazyhouse::tests::game("k7/1Q~6/8/8/8/8/8/4K3 b - - 0 1");
+
+        game.make_move(&"a8b7".parse().unwrap()).unwrap();
+
+        assert_eq!(game.get_pocket(&Turn::Black), [Piece::Pawn { state: PawnState::Default }]);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::board::{Board, GameState, MoveType, Termination, Turn};
+use crate::piece::shorthands::*;
+use crate::piece::{Color, PawnState, Piece};
+use crate::position::{BoardPosition, File::*, Rank::*, FILE};
+use crate::variant::{Standard, Variant};
+use crate::ChessGame;
+
+/// Chess where white starts with 36 pawns and no king. White wins by checkmating black and black
+/// wins by capturing every white piece. White pawns on the first rank can move two squares.
+pub struct Horde;
+
+impl Variant for Horde {
+    fn name(&self) -> &'static str {
+        "horde"
+    }
+
+    fn start_position(&self) -> Board<Color<Piece>> {
+        let mut board = Board::standard_position();
+
+        for file in FILE.into_iter() {
+            board.set(&BoardPosition::from((file.clone(), One)), Some(NEW_WHITE_PAWN));
+            board.set(&BoardPosition::from((file.clone(), Two)), Some(NEW_WHITE_PAWN));
+            board.set(&BoardPosition::from((file.clone(), Three)), Some(WHITE_PAWN));
+            board.set(&BoardPosition::from((file, Four)), Some(WHITE_PAWN));
+        }
+
+        for file in [B, C, F, G] {
+            board.set(&BoardPosition::from((file, Five)), Some(WHITE_PAWN));
+        }
+
+        board
+    }
+
+    fn after_move(
+        &self,
+        game: &mut ChessGame,
+        _initial_position: &BoardPosition,
+        desired_position: &BoardPosition,
+        _move_type: &MoveType,
+        _captured: &Option<Color<Piece>>,
+    ) {
+        // A pawn moved from the first rank can still move two squares from the second rank
+        if matches!(desired_position.get_rank(), Two)
+            && matches!(game.board.get(desired_position), Some(Color::White(Piece::Pawn { .. })))
+        {
+            game.board.set(
+                desired_position,
+                Some(Color::White(Piece::Pawn {
+                    state: PawnState::FirstMove,
+                })),
+            );
+        }
+    }
+
+    fn game_state(&self, game: &ChessGame) -> GameState {
+        if !game.iter().any(|piece| matches!(piece, Some(Color::White(_)))) {
+            GameState::Win(Turn::Black, Termination::AllPiecesLost)
+        } else {
+            Standard.game_state(game)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::GameResult;
+
+    fn game(fen: &str) -> ChessGame {
+        ChessGame::from_fen_with_variant(String::from(fen), Horde).unwrap()
+    }
+
+    #[test]
+    fn start_position() {
+        let mut game = ChessGame::new(Horde);
+
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1"
+        );
+        assert!(game.get_king_position(&Turn::White).is_none());
+
+        for chess_move in ["f5f6", "e7f6", "g5f6", "g8f6", "a4a5"] {
+            game.make_move(&chess_move.parse().unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn first_rank_pawns_move_two_squares() {
+        let mut game = game("4k3/8/8/8/8/8/8/P7 w - - 0 1");
+
+        assert!(game.get_valid_moves(&(A, One).into()).get(&(A, Three).into()).is_some());
+
+        game.make_move(&"a1a2".parse().unwrap()).unwrap();
+        game.make_move(&"e8e7".parse().unwrap()).unwrap();
+
+        assert!(game.get_valid_moves(&(A, Two).into()).get(&(A, Four).into()).is_some());
+    }
+
+    #[test]
+    fn capturing_every_white_piece_wins() {
+        let mut game = game("4k3/8/8/8/8/8/8/Pr6 b - - 0 1");
+
+        game.make_move(&"b1a1".parse().unwrap()).unwrap();
+
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::Black),
+                termination: Termination::AllPiecesLost,
+            })
+        );
+    }
+
+    #[test]
+    fn checkmate_wins_for_white() {
+        let mut game = game("7k/5P1p/5PP1/8/8/8/8/8 w - - 0 1");
+
+        game.make_move(&"g6g7".parse().unwrap()).unwrap();
+
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::White),
+                termination: Termination::CheckMate,
+            })
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use crate::board::{GameState, Termination};
+use crate::position::{BoardPosition, File::*, Rank::*};
+use crate::variant::{Standard, Variant};
+use crate::ChessGame;
+
+/// Standard chess where a player also wins by moving their king to one of the four center squares
+pub struct KingOfTheHill;
+
+impl KingOfTheHill {
+    /// Returns true if the position is one of the four center squares, d4, d5, e4 or e5
+    pub fn is_hill(position: &BoardPosition) -> bool {
+        matches!(position.get_file(), D | E) && matches!(position.get_rank(), Four | Five)
+    }
+}
+
+impl Variant for KingOfTheHill {
+    fn name(&self) -> &'static str {
+        "king of the hill"
+    }
+
+    fn game_state(&self, game: &ChessGame) -> GameState {
+        // The player that just moved is the only one that can have reached the hill
+        let opponent = game.get_player_turn().opponent();
+
+        if game.get_king_position(&opponent).is_some_and(KingOfTheHill::is_hill) {
+            GameState::Win(opponent, Termination::KingOfTheHill)
+        } else {
+            Standard.game_state(game)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{GameResult, Turn};
+
+    #[test]
+    fn king_on_hill_wins() {
+        let mut game = ChessGame::new(KingOfTheHill);
+
+        game.move_piece(&(E, Two).into(), &(E, Four).into()).unwrap();
+        game.move_piece(&(A, Seven).into(), &(A, Six).into()).unwrap();
+        game.move_piece(&(E, One).into(), &(E, Two).into()).unwrap();
+        game.move_piece(&(A, Six).into(), &(A, Five).into()).unwrap();
+        game.move_piece(&(E, Two).into(), &(D, Three).into()).unwrap();
+        game.move_piece(&(A, Five).into(), &(A, Four).into()).unwrap();
+
+        assert!(game.get_result().is_none());
+
+        game.move_piece(&(D, Three).into(), &(D, Four).into()).unwrap();
+
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::White),
+                termination: Termination::KingOfTheHill,
+            })
+        );
+    }
+
+    #[test]
+    fn is_hill() {
+        assert!(KingOfTheHill::is_hill(&(D, Five).into()));
+        assert!(KingOfTheHill::is_hill(&(E, Four).into()));
+        assert!(!KingOfTheHill::is_hill(&(C, Four).into()));
+        assert!(!KingOfTheHill::is_hill(&(E, Six).into()));
+    }
+}
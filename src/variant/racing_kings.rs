@@ -0,0 +1,205 @@
+use crate::board::{Board, GameState, Termination, Turn};
+use crate::piece::shorthands::*;
+use crate::piece::{Color, Piece};
+use crate::position::{BoardPosition, Rank::*, FILE};
+use crate::variant::{Standard, Variant};
+use crate::ChessGame;
+
+/// Chess without pawns where the first king to reach the eighth rank wins. Giving check is not
+/// allowed, if black reaches the eighth rank on the move after white the game is drawn.
+pub struct RacingKings;
+
+impl RacingKings {
+    /// Returns true if the king of `player_color` is on the eighth rank
+    fn has_finished(game: &ChessGame, player_color: &Turn) -> bool {
+        matches!(
+            game.get_king_position(player_color).map(|position| position.get_rank()),
+            Some(Eight)
+        )
+    }
+
+    /// Returns true if the black king can move to the eighth rank
+    fn black_can_finish(game: &ChessGame) -> bool {
+        let Some(king_position) = game.get_king_position(&Turn::Black) else {
+            return false;
+        };
+
+        let moves = game.get_valid_moves(king_position);
+
+        FILE.into_iter().any(|file| moves.get(&BoardPosition::from((file, Eight))).is_some())
+    }
+}
+
+impl Variant for RacingKings {
+    fn name(&self) -> &'static str {
+        "racing kings"
+    }
+
+    fn start_position(&self) -> Board<Color<Piece>> {
+        let mut board = Board::default();
+
+        let second_rank = [
+            BLACK_KING,
+            BLACK_ROOK,
+            BLACK_BISHOP,
+            BLACK_KNIGHT,
+            WHITE_KNIGHT,
+            WHITE_BISHOP,
+            WHITE_ROOK,
+            WHITE_KING,
+        ];
+        let first_rank = [
+            BLACK_QUEEN,
+            BLACK_ROOK,
+            BLACK_BISHOP,
+            BLACK_KNIGHT,
+            WHITE_KNIGHT,
+            WHITE_BISHOP,
+            WHITE_ROOK,
+            WHITE_QUEEN,
+        ];
+
+        for ((file, second), first) in FILE.into_iter().zip(second_rank).zip(first_rank) {
+            board.set(&BoardPosition::from((file.clone(), Two)), Some(second));
+            board.set(&BoardPosition::from((file, One)), Some(first));
+        }
+
+        board
+    }
+
+    fn is_legal(&self, game: &ChessGame, player_color: &Turn) -> bool {
+        // Neither king can ever be in check
+        !game.is_in_check(player_color) && !game.is_in_check(&player_color.opponent())
+    }
+
+    fn allows_castling(&self) -> bool {
+        false
+    }
+
+    fn game_state(&self, game: &ChessGame) -> GameState {
+        let white_finished = RacingKings::has_finished(game, &Turn::White);
+
+        match game.get_player_turn() {
+            // Black has made the last move, both players have had the same number of moves
+            Turn::White => match (white_finished, RacingKings::has_finished(game, &Turn::Black)) {
+                (true, true) => return GameState::Draw(Termination::RaceFinished),
+                (true, false) => return GameState::Win(Turn::White, Termination::RaceFinished),
+                (false, true) => return GameState::Win(Turn::Black, Termination::RaceFinished),
+                (false, false) => (),
+            },
+            // Black gets one more move to draw the game if it can reach the eighth rank
+            Turn::Black if white_finished => {
+                return if RacingKings::black_can_finish(game) {
+                    GameState::Ongoing
+                } else {
+                    GameState::Win(Turn::White, Termination::RaceFinished)
+                };
+            }
+            Turn::Black => (),
+        }
+
+        if !game.has_valid_moves() {
+            GameState::Draw(Termination::Stalemate)
+        } else {
+            Standard.game_state(game)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::GameResult;
+    use crate::position::File::*;
+
+    #[test]
+    fn start_position() {
+        let game = ChessGame::new(RacingKings);
+
+        assert_eq!(game.to_fen(), "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1");
+    }
+
+    #[test]
+    fn giving_check_is_not_allowed() {
+        let game = ChessGame::from_fen_with_variant(
+            String::from("8/8/8/8/8/8/k7/6RK w - - 0 1"),
+            RacingKings,
+        )
+        .unwrap();
+
+        let moves = game.get_valid_moves(&(G, One).into());
+
+        assert!(moves.get(&(A, One).into()).is_none());
+        assert!(moves.get(&(G, Two).into()).is_none());
+        assert!(moves.get(&(G, Three).into()).is_some());
+    }
+
+    #[test]
+    fn first_king_on_the_last_rank_wins() {
+        let mut game = ChessGame::from_fen_with_variant(
+            String::from("8/6K1/8/8/8/8/k7/8 w - - 0 1"),
+            RacingKings,
+        )
+        .unwrap();
+
+        game.move_piece(&(G, Seven).into(), &(G, Eight).into()).unwrap();
+
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::White),
+                termination: Termination::RaceFinished,
+            })
+        );
+
+        let mut game = ChessGame::from_fen_with_variant(
+            String::from("8/k7/8/8/8/8/6K1/8 b - - 0 1"),
+            RacingKings,
+        )
+        .unwrap();
+
+        game.move_piece(&(A, Seven).into(), &(A, Eight).into()).unwrap();
+
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::Black),
+                termination: Termination::RaceFinished,
+            })
+        );
+    }
+
+    #[test]
+    fn black_can_draw_on_the_next_move() {
+        let mut game = ChessGame::from_fen_with_variant(
+            String::from("8/k5K1/8/8/8/8/8/8 w - - 0 1"),
+            RacingKings,
+        )
+        .unwrap();
+
+        game.move_piece(&(G, Seven).into(), &(G, Eight).into()).unwrap();
+        assert!(matches!(game.get_game_state(), GameState::Ongoing));
+
+        let mut other_reply = game.clone();
+
+        game.move_piece(&(A, Seven).into(), &(A, Eight).into()).unwrap();
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: None,
+                termination: Termination::RaceFinished,
+            })
+        );
+
+        other_reply
+            .move_piece(&(A, Seven).into(), &(A, Six).into())
+            .unwrap();
+        assert_eq!(
+            other_reply.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::White),
+                termination: Termination::RaceFinished,
+            })
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use crate::board::{GameState, Termination};
+use crate::variant::{Standard, Variant};
+use crate::ChessGame;
+
+/// Standard chess where a player also wins by putting the other player in check three times
+pub struct ThreeCheck;
+
+impl Variant for ThreeCheck {
+    fn name(&self) -> &'static str {
+        "three-check"
+    }
+
+    fn check_limit(&self) -> Option<u8> {
+        Some(3)
+    }
+
+    fn game_state(&self, game: &ChessGame) -> GameState {
+        let state = Standard.game_state(game);
+
+        if matches!(state, GameState::CheckMate) {
+            return state;
+        }
+
+        // The player that just moved is the only one that can have given a new check
+        let opponent = game.get_player_turn().opponent();
+
+        if Some(game.get_checks_given(&opponent)) >= self.check_limit() {
+            GameState::Win(opponent, Termination::ThreeCheck)
+        } else {
+            state
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{GameResult, Turn};
+    use crate::position::{File::*, Rank::*};
+
+    #[test]
+    fn third_check_wins() {
+        let mut game = ChessGame::new(ThreeCheck);
+
+        game.move_piece(&(E, Two).into(), &(E, Four).into()).unwrap();
+        game.move_piece(&(D, Seven).into(), &(D, Six).into()).unwrap();
+
+        for _ in 0..2 {
+            assert!(matches!(
+                game.move_piece(&(F, One).into(), &(B, Five).into()),
+                Ok(GameState::Check)
+            ));
+            game.move_piece(&(C, Eight).into(), &(D, Seven).into()).unwrap();
+            game.move_piece(&(B, Five).into(), &(F, One).into()).unwrap();
+            game.move_piece(&(D, Seven).into(), &(C, Eight).into()).unwrap();
+        }
+
+        assert_eq!(game.get_checks_given(&Turn::White), 2);
+        assert!(game.get_result().is_none());
+
+        game.move_piece(&(F, One).into(), &(B, Five).into()).unwrap();
+
+        assert_eq!(
+            game.get_result(),
+            Some(GameResult {
+                winner: Some(Turn::White),
+                termination: Termination::ThreeCheck,
+            })
+        );
+        assert!(game.to_fen().ends_with(" +3+0"));
+    }
+}